@@ -0,0 +1,75 @@
+//! Two-step game authority transfer instructions
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::{AuthorityProposed, AuthorityTransferred, CasinoError};
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    /// Current game authority
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn propose_authority_handler(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    propose_authority(&mut ctx.accounts.game_state, new_authority)
+}
+
+/// Nominate a new authority; proposing the default pubkey cancels a pending transfer
+pub fn propose_authority(game: &mut Account<GameState>, new_authority: Pubkey) -> Result<()> {
+    game.pending_authority = new_authority;
+
+    emit!(AuthorityProposed {
+        game: game.key(),
+        authority: game.authority,
+        pending_authority: new_authority,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    /// Proposed authority
+    pub new_authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        constraint = game_state.pending_authority == new_authority.key() @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn accept_handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    accept_authority(&mut ctx.accounts.game_state)
+}
+
+/// Hand the game over to its pending authority. Roles delegated by the
+/// previous authority revert to the new one, as they are at initialization.
+pub fn accept_authority(game: &mut Account<GameState>) -> Result<()> {
+    let previous = game.authority;
+
+    game.authority = game.pending_authority;
+    game.pending_authority = Pubkey::default();
+    game.operator = game.authority;
+    game.resolver = game.authority;
+    game.treasurer = game.authority;
+
+    emit!(AuthorityTransferred {
+        game: game.key(),
+        previous_authority: previous,
+        new_authority: game.authority,
+    });
+
+    msg!("Authority of {} transferred to {}", game.slug_as_str(), game.authority);
+
+    Ok(())
+}
@@ -0,0 +1,410 @@
+//! Coin flip auto-bet sessions executed by the resolver from an internal balance
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+
+use crate::state::*;
+use crate::instructions::balance::{credit_balance, debit_balance};
+use crate::instructions::profile::record_wager;
+use crate::instructions::referral::accrue_referral;
+use crate::instructions::responsible::check_gaming_limits;
+use crate::instructions::throttle::throttle_bet;
+use crate::{AutoBetEnded, AutoBetOpened, AutoBetStepped, CasinoError};
+
+/// Most bets a single auto-bet session may place
+pub const MAX_AUTOBET_BETS: u32 = 1_000;
+
+#[derive(Accounts)]
+pub struct OpenAutoBet<'info> {
+    /// Player (pays rent and prepays the platform fee for every bet)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Coin flip game
+    #[account(
+        constraint = game_state.is_active @ CasinoError::GameNotActive,
+        constraint = game_state.game_type == GameType::CoinFlip @ CasinoError::GameNotActive,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Shared bankroll the session bets from
+    #[account(
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+        constraint = game_state.accepts_mint(&bankroll.mint) @ CasinoError::MintNotAccepted,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Player's internal balance funding the session
+    #[account(
+        seeds = [b"balance", bankroll.key().as_ref(), player.key().as_ref()],
+        bump = player_balance.bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    /// Limits for a non-primary mint (required when betting in one)
+    #[account(
+        seeds = [b"mint_config", game_state.key().as_ref(), bankroll.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Auto-bet session PDA
+    #[account(
+        init,
+        payer = player,
+        space = AutoBet::LEN,
+        seeds = [b"autobet", game_state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub autobet: Account<'info, AutoBet>,
+
+    /// Player profile PDA (stats and gaming limits)
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::LEN,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Per-game bet throttle PDA (cooldown and bets per window)
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerThrottle::LEN,
+        seeds = [b"throttle", game_state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_throttle: Box<Account<'info, PlayerThrottle>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn open_handler(
+    ctx: Context<OpenAutoBet>,
+    choice: CoinChoice,
+    strategy: AutoBetStrategy,
+) -> Result<()> {
+    let game = &ctx.accounts.game_state;
+    require!(
+        ctx.accounts.bankroll.mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+
+    let (min_bet, max_bet) = game.bet_limits(ctx.accounts.mint_config.as_deref());
+    require!(
+        (min_bet..=max_bet).contains(&strategy.base_bet)
+            && (1..=MAX_AUTOBET_BETS).contains(&strategy.bet_count),
+        CasinoError::InvalidAutoBet
+    );
+
+    // Platform fees for every bet are held by the session and returned on close
+    let fees = game
+        .config
+        .platform_fee_lamports
+        .checked_mul(strategy.bet_count as u64)
+        .ok_or(CasinoError::InvalidAutoBet)?;
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.player.to_account_info(),
+                to: ctx.accounts.autobet.to_account_info(),
+            },
+        ),
+        fees,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.player_profile.ensure_initialized(
+        ctx.accounts.player.key(),
+        ctx.bumps.player_profile,
+        now,
+    );
+    let throttle = &mut ctx.accounts.player_throttle;
+    if throttle.player == Pubkey::default() {
+        throttle.version = PlayerThrottle::VERSION;
+        throttle.player = ctx.accounts.player.key();
+        throttle.game = ctx.accounts.game_state.key();
+        throttle.bump = ctx.bumps.player_throttle;
+    }
+
+    let autobet = &mut ctx.accounts.autobet;
+    autobet.version = AutoBet::VERSION;
+    autobet.player = ctx.accounts.player.key();
+    autobet.game = ctx.accounts.game_state.key();
+    autobet.bankroll = ctx.accounts.bankroll.key();
+    autobet.choice = choice;
+    autobet.strategy = strategy;
+    autobet.current_bet = strategy.base_bet;
+    autobet.bets_placed = 0;
+    autobet.wagered = 0;
+    autobet.won = 0;
+    autobet.active = true;
+    autobet.created_at = now;
+    autobet.bump = ctx.bumps.autobet;
+
+    emit!(AutoBetOpened {
+        game: autobet.game,
+        player: autobet.player,
+        choice,
+        strategy,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AutoBetStep<'info> {
+    /// VRF authority (game resolver)
+    pub vrf_authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        constraint = game_state.resolver == vrf_authority.key() @ CasinoError::Unauthorized,
+        constraint = game_state.is_active @ CasinoError::GameNotActive,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Global config (bets are refused during an emergency stop)
+    #[account(
+        seeds = [b"global"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ CasinoError::EmergencyStop,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Auto-bet session to advance
+    #[account(
+        mut,
+        constraint = autobet.active @ CasinoError::AutoBetInactive,
+        seeds = [b"autobet", game_state.key().as_ref(), autobet.player.as_ref()],
+        bump = autobet.bump,
+    )]
+    pub autobet: Account<'info, AutoBet>,
+
+    /// Shared bankroll holding the player's balance
+    #[account(
+        mut,
+        address = autobet.bankroll,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Shared vault
+    #[account(
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Player's internal balance
+    #[account(
+        mut,
+        seeds = [b"balance", bankroll.key().as_ref(), autobet.player.as_ref()],
+        bump = player_balance.bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    /// Limits and accounting for a non-primary mint (required when betting in one)
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), bankroll.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Player profile (stats, gaming limits and rakeback)
+    #[account(
+        mut,
+        seeds = [b"profile", autobet.player.as_ref()],
+        bump = player_profile.bump,
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Per-game bet throttle
+    #[account(
+        mut,
+        seeds = [b"throttle", game_state.key().as_ref(), autobet.player.as_ref()],
+        bump = player_throttle.bump,
+    )]
+    pub player_throttle: Box<Account<'info, PlayerThrottle>>,
+
+    /// VIP tier configuration (no rakeback when absent)
+    #[account(
+        constraint = vip_config.bankroll == bankroll.key() @ CasinoError::WrongBankroll,
+    )]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    /// Referrer earnings PDA (required when the player has a referrer)
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn step_handler(ctx: Context<AutoBetStep>, vrf_result: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let bet_mint = ctx.accounts.bankroll.mint;
+    let stake = ctx.accounts.autobet.current_bet;
+
+    // A withdrawn balance ends the session rather than failing every step
+    if ctx.accounts.player_balance.amount < stake {
+        return end_session(&mut ctx.accounts.autobet, AutoBetStop::OutOfFunds);
+    }
+
+    let game = &ctx.accounts.game_state;
+    require!(
+        bet_mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+    let (min_bet, max_bet) = game.bet_limits(ctx.accounts.mint_config.as_deref());
+    require!(stake >= min_bet, CasinoError::BetTooSmall);
+    require!(stake <= max_bet, CasinoError::BetTooLarge);
+
+    // Same liquidity checks as a manual bet; balance payouts never leave the vault
+    let multiplier = 20000 - (game.config.house_edge_bps as u64 * 2);
+    let potential_payout = (stake * multiplier) / 10000;
+    require!(
+        ctx.accounts.bankroll.liquidity(ctx.accounts.vault.amount) >= potential_payout,
+        CasinoError::InsufficientEscrow
+    );
+    require!(
+        potential_payout <= game.liquidity_for(ctx.accounts.mint_config.as_deref()),
+        CasinoError::AllocationExceeded
+    );
+
+    let throttle_bump = ctx.accounts.player_throttle.bump;
+    throttle_bet(
+        &ctx.accounts.game_state,
+        &mut ctx.accounts.player_throttle,
+        ctx.accounts.autobet.player,
+        throttle_bump,
+        clock.unix_timestamp,
+    )?;
+    let tracks_rewards = ctx.accounts.game_state.tracks_rewards(&bet_mint);
+    check_gaming_limits(
+        &mut ctx.accounts.player_profile,
+        stake,
+        tracks_rewards,
+        clock.unix_timestamp,
+    )?;
+
+    // Flip
+    let won = calculate_coinflip_result(&vrf_result) == ctx.accounts.autobet.choice;
+    let payout = if won { potential_payout } else { 0 };
+
+    debit_balance(&mut ctx.accounts.bankroll, &mut ctx.accounts.player_balance, stake)?;
+    if payout > 0 {
+        credit_balance(&mut ctx.accounts.bankroll, &mut ctx.accounts.player_balance, payout)?;
+    }
+
+    // Platform fee from the session's prepaid lamports
+    let fee = take_fee(
+        &ctx.accounts.autobet.to_account_info(),
+        &ctx.accounts.game_state.to_account_info(),
+        ctx.accounts.game_state.config.platform_fee_lamports,
+    )?;
+
+    let game = &mut ctx.accounts.game_state;
+    game.add_volume(ctx.accounts.mint_config.as_deref_mut(), stake);
+    game.settle(ctx.accounts.mint_config.as_deref_mut(), stake, payout);
+    game.total_fees = game.total_fees.checked_add(fee).unwrap();
+
+    // Volume, rakeback and referral rewards are denominated in the primary bankroll token
+    if tracks_rewards {
+        record_wager(
+            &mut ctx.accounts.game_state,
+            &mut ctx.accounts.bankroll,
+            &mut ctx.accounts.player_profile,
+            ctx.accounts.vip_config.as_ref(),
+            stake,
+            clock.unix_timestamp,
+        )?;
+        accrue_referral(
+            &mut ctx.accounts.game_state,
+            &mut ctx.accounts.bankroll,
+            &ctx.accounts.player_profile,
+            ctx.accounts.referrer_account.as_mut(),
+            stake,
+        )?;
+        ctx.accounts.player_profile.record_result(stake, payout);
+    }
+
+    // The next bet follows the strategy, kept within the game's limits
+    let autobet = &mut ctx.accounts.autobet;
+    autobet.bets_placed += 1;
+    autobet.wagered = autobet.wagered.checked_add(stake).unwrap();
+    autobet.won = autobet.won.checked_add(payout).unwrap();
+    autobet.current_bet = autobet.strategy.next_bet(stake, won).clamp(min_bet, max_bet);
+
+    emit!(AutoBetStepped {
+        game: autobet.game,
+        player: autobet.player,
+        bet_amount: stake,
+        won,
+        payout,
+        bets_placed: autobet.bets_placed,
+        vrf_proof: vrf_result,
+    });
+
+    match autobet.stop_reason() {
+        Some(reason) => end_session(autobet, reason),
+        None => Ok(()),
+    }
+}
+
+#[derive(Accounts)]
+pub struct CloseAutoBet<'info> {
+    /// Player (receives the rent and unused prepaid fees)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Auto-bet session to stop and close
+    #[account(
+        mut,
+        close = player,
+        has_one = player @ CasinoError::Unauthorized,
+    )]
+    pub autobet: Account<'info, AutoBet>,
+}
+
+pub fn close_handler(ctx: Context<CloseAutoBet>) -> Result<()> {
+    let autobet = &mut ctx.accounts.autobet;
+    if autobet.active {
+        end_session(autobet, AutoBetStop::Cancelled)?;
+    }
+    Ok(())
+}
+
+fn end_session(autobet: &mut Account<AutoBet>, reason: AutoBetStop) -> Result<()> {
+    autobet.active = false;
+
+    emit!(AutoBetEnded {
+        game: autobet.game,
+        player: autobet.player,
+        reason,
+        bets_placed: autobet.bets_placed,
+        wagered: autobet.wagered,
+        won: autobet.won,
+    });
+
+    Ok(())
+}
+
+/// Move up to `fee` prepaid lamports from the session to the game, keeping
+/// the session rent-exempt
+fn take_fee(autobet: &AccountInfo, game: &AccountInfo, fee: u64) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(autobet.data_len());
+    let amount = fee.min(autobet.lamports().saturating_sub(rent_exempt));
+
+    **autobet.try_borrow_mut_lamports()? -= amount;
+    **game.try_borrow_mut_lamports()? += amount;
+
+    Ok(amount)
+}
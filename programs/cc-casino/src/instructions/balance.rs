@@ -0,0 +1,189 @@
+//! Internal player balances held in a bankroll vault
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::*;
+use crate::transfer::{transfer_from_vault, transfer_to_vault};
+use crate::{BalanceDeposited, BalanceWithdrawn, CasinoError};
+
+#[derive(Accounts)]
+pub struct DepositBalance<'info> {
+    /// Player (pays for the balance account if it doesn't exist yet)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Player balance PDA
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerBalance::LEN,
+        seeds = [b"balance", bankroll.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    /// Player's token account
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bankroll token mint
+    #[account(address = bankroll.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Shared vault token account
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn deposit_handler(ctx: Context<DepositBalance>, amount: u64) -> Result<()> {
+    require!(amount > 0, CasinoError::DepositTooSmall);
+
+    // Credit what actually arrived after transfer fees
+    let received = transfer_to_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.player_token_account,
+        &ctx.accounts.vault,
+        &ctx.accounts.player.to_account_info(),
+        amount,
+    )?;
+
+    let balance = &mut ctx.accounts.player_balance;
+    if balance.player == Pubkey::default() {
+        balance.version = PlayerBalance::VERSION;
+        balance.player = ctx.accounts.player.key();
+        balance.bankroll = ctx.accounts.bankroll.key();
+        balance.bump = ctx.bumps.player_balance;
+    }
+    credit_balance(&mut ctx.accounts.bankroll, balance, received)?;
+
+    emit!(BalanceDeposited {
+        player: balance.player,
+        bankroll: balance.bankroll,
+        amount: received,
+        balance: balance.amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBalance<'info> {
+    /// Player
+    pub player: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Player balance PDA
+    #[account(
+        mut,
+        seeds = [b"balance", bankroll.key().as_ref(), player.key().as_ref()],
+        bump = player_balance.bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    /// Player's token account
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bankroll token mint
+    #[account(address = bankroll.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Shared vault token account
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn withdraw_balance_handler(ctx: Context<WithdrawBalance>, amount: u64) -> Result<()> {
+    debit_balance(&mut ctx.accounts.bankroll, &mut ctx.accounts.player_balance, amount)?;
+
+    transfer_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.bankroll,
+        &ctx.accounts.vault,
+        &ctx.accounts.player_token_account,
+        amount,
+    )?;
+
+    let balance = &ctx.accounts.player_balance;
+    emit!(BalanceWithdrawn {
+        player: balance.player,
+        bankroll: balance.bankroll,
+        amount,
+        balance: balance.amount,
+    });
+
+    Ok(())
+}
+
+/// Move `amount` from bankroll liquidity into a player's balance
+pub fn credit_balance(
+    bankroll: &mut Bankroll,
+    balance: &mut PlayerBalance,
+    amount: u64,
+) -> Result<()> {
+    balance.amount = balance.amount.checked_add(amount).unwrap();
+    bankroll.player_balances = bankroll.player_balances.checked_add(amount).unwrap();
+    Ok(())
+}
+
+/// Take `amount` out of a player's balance (back into bankroll liquidity
+/// unless it is being withdrawn)
+pub fn debit_balance(
+    bankroll: &mut Bankroll,
+    balance: &mut PlayerBalance,
+    amount: u64,
+) -> Result<()> {
+    balance.amount = balance
+        .amount
+        .checked_sub(amount)
+        .ok_or(CasinoError::InsufficientBalance)?;
+    bankroll.player_balances = bankroll.player_balances.saturating_sub(amount);
+    Ok(())
+}
@@ -0,0 +1,181 @@
+//! Shared bankroll instructions
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::state::*;
+use crate::transfer::required;
+use crate::{AllocationUpdated, BankrollInitialized, CasinoError};
+
+#[derive(Accounts)]
+pub struct InitializeBankroll<'info> {
+    /// Bankroll authority (signer, pays for account creation)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Bankroll PDA
+    #[account(
+        init,
+        payer = authority,
+        space = Bankroll::LEN,
+        seeds = [b"bankroll", mint.key().as_ref()],
+        bump
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Shared vault token account (holds liquidity for all games)
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token mint for this bankroll
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Liquidity share mint (minted to depositors, authority = bankroll)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"shares", bankroll.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = bankroll,
+        mint::token_program = token_program,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Holds shares locked in pending withdrawal requests
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = share_mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub share_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn initialize_bankroll_handler(ctx: Context<InitializeBankroll>, withdrawal_delay: i64) -> Result<()> {
+    require!(withdrawal_delay >= 0, CasinoError::InvalidWithdrawalDelay);
+
+    let bankroll = &mut ctx.accounts.bankroll;
+    let clock = Clock::get()?;
+
+    bankroll.authority = ctx.accounts.authority.key();
+    bankroll.mint = ctx.accounts.mint.key();
+    bankroll.total_allocated = 0;
+    bankroll.game_count = 0;
+    bankroll.created_at = clock.unix_timestamp;
+    bankroll.version = Bankroll::VERSION;
+    bankroll.bump = ctx.bumps.bankroll;
+    bankroll.share_mint = ctx.accounts.share_mint.key();
+    bankroll.withdrawal_delay = withdrawal_delay;
+    bankroll.pending_withdrawal_shares = 0;
+
+    emit!(BankrollInitialized {
+        bankroll: bankroll.key(),
+        mint: bankroll.mint,
+        authority: bankroll.authority,
+        share_mint: bankroll.share_mint,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RebalanceAllocation<'info> {
+    /// Bankroll authority
+    pub authority: Signer<'info>,
+
+    /// Bankroll
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Game whose allocation is being changed
+    #[account(
+        mut,
+        constraint = game_state.accepts_mint(&bankroll.mint) @ CasinoError::WrongBankroll,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Mint config (required when the bankroll is not the game's primary mint)
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), bankroll.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Shared vault
+    #[account(
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn rebalance_handler(ctx: Context<RebalanceAllocation>, allocation: u64) -> Result<()> {
+    let bankroll = &mut ctx.accounts.bankroll;
+    let game = &mut ctx.accounts.game_state;
+
+    // The primary mint's allocation lives on the game, others on their MintConfig
+    let current = if bankroll.mint == game.cc_mint {
+        &mut game.allocation
+    } else {
+        &mut required(ctx.accounts.mint_config.as_deref_mut())?.allocation
+    };
+
+    // Allocations across all games can never exceed the vault's liquidity
+    let total_allocated = bankroll
+        .total_allocated
+        .checked_sub(*current)
+        .unwrap()
+        .checked_add(allocation)
+        .unwrap();
+    require!(
+        total_allocated <= bankroll.liquidity(ctx.accounts.vault.amount),
+        CasinoError::InsufficientEscrow
+    );
+
+    bankroll.total_allocated = total_allocated;
+    *current = allocation;
+
+    emit!(AllocationUpdated {
+        bankroll: bankroll.key(),
+        game: game.key(),
+        allocation,
+        total_allocated,
+    });
+
+    msg!(
+        "Allocation for {} set to {} tokens ({} allocated in total)",
+        game.slug_as_str(),
+        allocation,
+        total_allocated
+    );
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! House profit burn instructions
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface};
+
+use crate::state::*;
+use crate::{Burned, CasinoError};
+
+#[derive(Accounts)]
+pub struct SetBurnShare<'info> {
+    /// Authority (must match game authority)
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn set_burn_share_handler(ctx: Context<SetBurnShare>, burn_bps: u16) -> Result<()> {
+    set_burn_share(&mut ctx.accounts.game_state, burn_bps)
+}
+
+pub fn set_burn_share(game: &mut Account<GameState>, burn_bps: u16) -> Result<()> {
+    require!(burn_bps <= 10000, CasinoError::InvalidBurnShare);
+
+    game.burn_bps = burn_bps;
+
+    msg!("Burn share for {} set to {} bps", game.slug_as_str(), burn_bps);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BurnHouseProfit<'info> {
+    /// Crank (anyone can trigger a burn)
+    pub cranker: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        constraint = game_state.currency == Currency::Token @ CasinoError::WrongCurrency,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Shared bankroll
+    #[account(
+        seeds = [b"bankroll", game_state.cc_mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// $CC token mint (supply is reduced by the burn)
+    #[account(
+        mut,
+        address = game_state.cc_mint,
+    )]
+    pub cc_mint: InterfaceAccount<'info, Mint>,
+
+    /// Shared vault token account
+    #[account(
+        mut,
+        associated_token::mint = game_state.cc_mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn burn_handler(ctx: Context<BurnHouseProfit>) -> Result<()> {
+    let game = &ctx.accounts.game_state;
+
+    // Burn the configured share of profit realized since the last burn
+    let amount = game.pending_burn();
+    require!(amount > 0, CasinoError::NothingToBurn);
+    require!(
+        ctx.accounts.bankroll.liquidity(ctx.accounts.vault.amount) >= amount,
+        CasinoError::InsufficientEscrow
+    );
+
+    let bankroll = &ctx.accounts.bankroll;
+    let seeds = &[b"bankroll".as_ref(), bankroll.mint.as_ref(), &[bankroll.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.cc_mint.to_account_info(),
+        from: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.bankroll.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::burn(cpi_ctx, amount)?;
+
+    let game = &mut ctx.accounts.game_state;
+    game.total_burned = game.total_burned.checked_add(amount).unwrap();
+    game.burned_profit_mark = game.realized_profit;
+
+    emit!(Burned {
+        game: game.key(),
+        amount,
+        realized_profit: game.realized_profit,
+        total_burned: game.total_burned,
+    });
+
+    msg!("Burned {} tokens from vault. Total burned: {}", amount, game.total_burned);
+
+    Ok(())
+}
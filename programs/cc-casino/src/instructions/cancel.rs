@@ -0,0 +1,333 @@
+//! Cancellation of pending bets and pulls before their randomness is revealed
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::balance::credit_balance;
+use crate::state::*;
+use crate::transfer::{required, GameFunds};
+use crate::{BetCancelled, CasinoError};
+
+#[derive(Accounts)]
+pub struct SetCancelPolicy<'info> {
+    /// Authority (must match game authority)
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn set_policy_handler(
+    ctx: Context<SetCancelPolicy>,
+    cancel_window_slots: u64,
+    cancel_fee_bps: u16,
+) -> Result<()> {
+    set_cancel_policy(&mut ctx.accounts.game_state, cancel_window_slots, cancel_fee_bps)
+}
+
+pub fn set_cancel_policy(
+    game: &mut Account<GameState>,
+    cancel_window_slots: u64,
+    cancel_fee_bps: u16,
+) -> Result<()> {
+    // The window must close before the resolution timeout can expire
+    require!(
+        cancel_fee_bps <= 10000 && cancel_window_slots < game.resolution_timeout_slots,
+        CasinoError::InvalidCancelPolicy
+    );
+
+    game.cancel_window_slots = cancel_window_slots;
+    game.cancel_fee_bps = cancel_fee_bps;
+
+    msg!(
+        "Cancel window for {} set to {} slots at a {} bps fee",
+        game.slug_as_str(),
+        cancel_window_slots,
+        cancel_fee_bps
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelBet<'info> {
+    /// Player (receives the refund)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Game state
+    #[account(mut)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Pending coin flip bet
+    #[account(
+        mut,
+        close = rent_receiver,
+        constraint = player_bet.outcome == BetOutcome::Pending @ CasinoError::AlreadyResolved,
+        seeds = [b"bet", game_state.key().as_ref(), player.key().as_ref()],
+        bump = player_bet.bump,
+    )]
+    pub player_bet: Account<'info, PlayerBet>,
+
+    /// Wallet that paid the bet's rent (the player or a relayer)
+    /// CHECK: Matched against the recorded rent payer
+    #[account(mut, address = player_bet.rent_payer @ CasinoError::WrongRecipient)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// Player's token account (token games)
+    #[account(
+        mut,
+        associated_token::mint = player_bet.mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint the bet was placed in (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", player_bet.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint the bet was placed in (token games)
+    #[account(address = player_bet.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = player_bet.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Ledger of a non-primary mint (required when the bet used one)
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), player_bet.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Player's internal balance (required when the stake came from it)
+    #[account(
+        mut,
+        constraint = player_balance.player == player_bet.player
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn cancel_bet_handler(ctx: Context<CancelBet>) -> Result<()> {
+    let player_bet = &ctx.accounts.player_bet;
+    let game = &ctx.accounts.game_state;
+    require!(
+        player_bet.mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+    require!(
+        game.cancel_window_open(player_bet.bet_slot, Clock::get()?.slot),
+        CasinoError::CancelWindowClosed
+    );
+
+    // The cancellation fee stays in the vault as house profit
+    let refund = cancel_refund(game, player_bet.bet_amount);
+    if player_bet.from_balance {
+        credit_balance(
+            required(ctx.accounts.bankroll.as_deref_mut())?,
+            required(ctx.accounts.player_balance.as_deref_mut())?,
+            refund,
+        )?;
+    } else if refund > 0 {
+        GameFunds::new(
+            &ctx.accounts.game_state,
+            ctx.accounts.mint.as_deref(),
+            ctx.accounts.bankroll.as_ref(),
+            ctx.accounts.vault.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            ctx.accounts.sol_vault.as_ref(),
+            &ctx.accounts.system_program,
+        )
+        .pay(
+            &ctx.accounts.player.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            refund,
+        )?;
+    }
+    ctx.accounts
+        .game_state
+        .settle(ctx.accounts.mint_config.as_deref_mut(), player_bet.bet_amount, refund);
+
+    emit!(BetCancelled {
+        game: ctx.accounts.game_state.key(),
+        player: ctx.accounts.player.key(),
+        amount: refund,
+        fee_amount: player_bet.bet_amount - refund,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelGachaPull<'info> {
+    /// Player (receives the refund)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Game state
+    #[account(mut)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Pending pull
+    #[account(
+        mut,
+        close = rent_receiver,
+        has_one = player @ CasinoError::Unauthorized,
+        constraint = pull_result.game == game_state.key() @ CasinoError::Unauthorized,
+        constraint = !pull_result.resolved @ CasinoError::AlreadyResolved,
+    )]
+    pub pull_result: Account<'info, GachaPullResult>,
+
+    /// Wallet that paid the pull's rent (the player or a relayer)
+    /// CHECK: Matched against the recorded rent payer
+    #[account(mut, address = pull_result.rent_payer @ CasinoError::WrongRecipient)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// Player's token account (token games)
+    #[account(
+        mut,
+        associated_token::mint = pull_result.mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint the pulls were paid in (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", pull_result.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint the pulls were paid in (token games)
+    #[account(address = pull_result.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = pull_result.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Ledger of a non-primary mint (required when the pulls used one)
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), pull_result.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Player's internal balance (required when the cost came from it)
+    #[account(
+        mut,
+        constraint = player_balance.player == pull_result.player
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn cancel_pull_handler(ctx: Context<CancelGachaPull>) -> Result<()> {
+    let pull_result = &ctx.accounts.pull_result;
+    let game = &ctx.accounts.game_state;
+    require!(
+        pull_result.mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+    require!(
+        game.cancel_window_open(pull_result.pulled_slot, Clock::get()?.slot),
+        CasinoError::CancelWindowClosed
+    );
+
+    // The cancellation fee stays in the vault as house profit
+    let refund = cancel_refund(game, pull_result.total_cost);
+    if pull_result.from_balance {
+        credit_balance(
+            required(ctx.accounts.bankroll.as_deref_mut())?,
+            required(ctx.accounts.player_balance.as_deref_mut())?,
+            refund,
+        )?;
+    } else if refund > 0 {
+        GameFunds::new(
+            &ctx.accounts.game_state,
+            ctx.accounts.mint.as_deref(),
+            ctx.accounts.bankroll.as_ref(),
+            ctx.accounts.vault.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            ctx.accounts.sol_vault.as_ref(),
+            &ctx.accounts.system_program,
+        )
+        .pay(
+            &ctx.accounts.player.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            refund,
+        )?;
+    }
+    ctx.accounts
+        .game_state
+        .settle(ctx.accounts.mint_config.as_deref_mut(), pull_result.total_cost, refund);
+
+    emit!(BetCancelled {
+        game: ctx.accounts.game_state.key(),
+        player: ctx.accounts.player.key(),
+        amount: refund,
+        fee_amount: pull_result.total_cost - refund,
+    });
+
+    Ok(())
+}
+
+/// Stake returned on cancellation after the game's cancellation fee
+fn cancel_refund(game: &GameState, stake: u64) -> u64 {
+    stake - ((stake as u128 * game.cancel_fee_bps as u128) / 10000) as u64
+}
@@ -0,0 +1,247 @@
+//! Close instructions that return the rent of settled accounts
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::state::*;
+use crate::CasinoError;
+
+/// How long after settlement anyone but the owner may close an account, so
+/// clients get a chance to read the result first
+const CLOSE_GRACE_SECONDS: i64 = 86_400;
+
+#[derive(Accounts)]
+pub struct CloseBet<'info> {
+    /// Player
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Resolved coin flip bet
+    #[account(
+        mut,
+        close = rent_receiver,
+        has_one = player @ CasinoError::Unauthorized,
+        constraint = player_bet.outcome != BetOutcome::Pending @ CasinoError::NotResolved,
+    )]
+    pub player_bet: Account<'info, PlayerBet>,
+
+    /// Wallet that paid the bet's rent (the player or a relayer)
+    /// CHECK: Matched against the recorded rent payer
+    #[account(mut, address = player_bet.rent_payer @ CasinoError::WrongRecipient)]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+pub fn close_bet_handler(_ctx: Context<CloseBet>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseGachaPull<'info> {
+    /// Player
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Resolved pull
+    #[account(
+        mut,
+        close = rent_receiver,
+        has_one = player @ CasinoError::Unauthorized,
+        constraint = pull_result.resolved @ CasinoError::NotResolved,
+    )]
+    pub pull_result: Account<'info, GachaPullResult>,
+
+    /// Wallet that paid the pull's rent (the player or a relayer)
+    /// CHECK: Matched against the recorded rent payer
+    #[account(mut, address = pull_result.rent_payer @ CasinoError::WrongRecipient)]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+pub fn close_pull_handler(_ctx: Context<CloseGachaPull>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseRoundEntry<'info> {
+    /// Player
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Ended round
+    #[account(
+        mut,
+        constraint = round_state.phase == RoundPhase::Ended @ CasinoError::NotResolved,
+    )]
+    pub round_state: Account<'info, RoundState>,
+
+    /// Player's entry in the round
+    #[account(
+        mut,
+        close = rent_receiver,
+        has_one = player @ CasinoError::Unauthorized,
+        constraint = participant.round == round_state.key() @ CasinoError::NotInRound,
+    )]
+    pub participant: Account<'info, RoundParticipant>,
+
+    /// Wallet that paid the entry's rent (the player or a relayer)
+    /// CHECK: Matched against the recorded rent payer
+    #[account(mut, address = participant.rent_payer @ CasinoError::WrongRecipient)]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+pub fn close_entry_handler(ctx: Context<CloseRoundEntry>) -> Result<()> {
+    let round = &mut ctx.accounts.round_state;
+    round.participant_count = round.participant_count.saturating_sub(1);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseRound<'info> {
+    /// Anyone (the rent goes back to whoever paid it)
+    pub closer: Signer<'info>,
+
+    /// Game the round belongs to
+    #[account(address = round_state.game)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Round with no entries left
+    #[account(
+        mut,
+        constraint = round_state.participant_count == 0 @ CasinoError::RoundHasEntries,
+    )]
+    pub round_state: Account<'info, RoundState>,
+
+    /// Wallet that paid the round's rent (the game authority for legacy rounds)
+    /// CHECK: Matched against the round's rent payer
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == round_state.rent_payer
+            || (round_state.rent_payer == Pubkey::default()
+                && rent_receiver.key() == game_state.authority)
+            @ CasinoError::WrongRecipient,
+    )]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+pub fn close_round_handler(ctx: Context<CloseRound>) -> Result<()> {
+    // Rounds that were never resolved can go once refunds have emptied them
+    let round = &ctx.accounts.round_state;
+    require!(
+        round.phase == RoundPhase::Ended
+            || ctx
+                .accounts
+                .game_state
+                .resolution_expired(round.started_slot, Clock::get()?.slot),
+        CasinoError::NotResolved
+    );
+
+    round.close(ctx.accounts.rent_receiver.to_account_info())
+}
+
+#[derive(Accounts)]
+pub struct CloseSettledBets<'info> {
+    /// Anyone; others than the owner only after the grace period
+    pub closer: Signer<'info>,
+    // remaining_accounts: (bet or pull result, rent payer) pairs, both writable
+}
+
+pub fn close_bets_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseSettledBets<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    for pair in pairs(ctx.remaining_accounts)? {
+        let (account, receiver) = (&pair[0], &pair[1]);
+        require!(account.owner == &crate::ID, CasinoError::InvalidRemainingAccounts);
+        let (player, rent_payer, settled_at) = {
+            let data = account.try_borrow_data()?;
+            if data.starts_with(&PlayerBet::DISCRIMINATOR) {
+                let bet = PlayerBet::try_deserialize(&mut &data[..])?;
+                require!(bet.outcome != BetOutcome::Pending, CasinoError::NotResolved);
+                (bet.player, bet.rent_payer, bet.resolved_at)
+            } else {
+                let pull = GachaPullResult::try_deserialize(&mut &data[..])?;
+                require!(pull.resolved, CasinoError::NotResolved);
+                (pull.player, pull.rent_payer, pull.resolved_at)
+            }
+        };
+
+        require!(receiver.key() == rent_payer, CasinoError::WrongRecipient);
+        close_settled(account, receiver, player, settled_at, &ctx.accounts.closer, now)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseRoundEntries<'info> {
+    /// Anyone; others than the owner only after the grace period
+    pub closer: Signer<'info>,
+
+    /// Ended round the entries belong to
+    #[account(
+        mut,
+        constraint = round_state.phase == RoundPhase::Ended @ CasinoError::NotResolved,
+    )]
+    pub round_state: Account<'info, RoundState>,
+    // remaining_accounts: (participant, rent payer) pairs, both writable
+}
+
+pub fn close_entries_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseRoundEntries<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let round = &mut ctx.accounts.round_state;
+
+    for pair in pairs(ctx.remaining_accounts)? {
+        let (account, receiver) = (&pair[0], &pair[1]);
+        require!(account.owner == &crate::ID, CasinoError::InvalidRemainingAccounts);
+        let participant =
+            RoundParticipant::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+        require!(participant.round == round.key(), CasinoError::NotInRound);
+        require!(receiver.key() == participant.rent_payer, CasinoError::WrongRecipient);
+
+        close_settled(
+            account,
+            receiver,
+            participant.player,
+            round.ended_at,
+            &ctx.accounts.closer,
+            now,
+        )?;
+        round.participant_count = round.participant_count.saturating_sub(1);
+    }
+
+    Ok(())
+}
+
+/// Split remaining accounts into (account, owner) pairs
+fn pairs<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+) -> Result<std::slice::ChunksExact<'a, AccountInfo<'info>>> {
+    let pairs = accounts.chunks_exact(2);
+    require!(pairs.remainder().is_empty(), CasinoError::InvalidRemainingAccounts);
+    Ok(pairs)
+}
+
+/// Close a settled account belonging to `player`, returning its rent to
+/// `receiver` (already checked to be the wallet that paid it)
+fn close_settled<'info>(
+    account: &AccountInfo<'info>,
+    receiver: &AccountInfo<'info>,
+    player: Pubkey,
+    settled_at: i64,
+    closer: &Signer<'info>,
+    now: i64,
+) -> Result<()> {
+    require!(
+        closer.key() == player || now >= settled_at.saturating_add(CLOSE_GRACE_SECONDS),
+        CasinoError::CloseTooEarly
+    );
+
+    **receiver.try_borrow_mut_lamports()? += account.lamports();
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&System::id());
+    account.realloc(0, false)?;
+
+    Ok(())
+}
@@ -1,16 +1,39 @@
 //! Coin flip game instructions
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::state::*;
+use crate::transfer::{required, GameFunds};
+use crate::instructions::balance::{credit_balance, debit_balance};
+use crate::instructions::profile::record_wager;
+use crate::instructions::referral::accrue_referral;
+use crate::instructions::responsible::check_gaming_limits;
+use crate::instructions::session::authorize_bet;
+use crate::instructions::throttle::throttle_bet;
 use crate::{BetPlaced, BetResolved, CasinoError};
 
 #[derive(Accounts)]
 pub struct PlayCoinflip<'info> {
-    /// Player (signer)
+    /// Wallet or session key signing the bet (native SOL stakes come from it)
     #[account(mut)]
-    pub player: Signer<'info>,
+    pub signer: Signer<'info>,
+
+    /// Player wallet (owns the bet; payouts go to it)
+    /// CHECK: Must be `signer` unless `session` authorizes it, see `authorize_bet`
+    pub player: UncheckedAccount<'info>,
+
+    /// Session authorizing `signer` to bet for `player`
+    #[account(
+        mut,
+        seeds = [b"session", player.key().as_ref(), signer.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, Session>>,
+
+    /// Relayer paying rent and platform fees (the signer pays when omitted)
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
 
     /// Game state
     #[account(
@@ -20,37 +43,118 @@ pub struct PlayCoinflip<'info> {
     )]
     pub game_state: Account<'info, GameState>,
 
+    /// Global config (bets are refused during an emergency stop)
+    #[account(
+        seeds = [b"global"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ CasinoError::EmergencyStop,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     /// Player bet PDA
     #[account(
         init,
-        payer = player,
+        payer = payer.as_ref().unwrap_or(&signer),
         space = PlayerBet::LEN,
         seeds = [b"bet", game_state.key().as_ref(), player.key().as_ref()],
         bump
     )]
     pub player_bet: Account<'info, PlayerBet>,
 
-    /// Player's token account
+    /// Player's token account (token games)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
+        associated_token::mint = mint,
         associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint being bet (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+        constraint = game_state.accepts_mint(&bankroll.mint) @ CasinoError::MintNotAccepted,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint being bet, SPL Token or Token-2022 (token games)
+    #[account(
+        constraint = bankroll.as_ref().is_some_and(|b| b.mint == mint.key()) @ CasinoError::WrongBankroll,
+    )]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Limits and accounting for a non-primary mint (required when betting in one)
+    #[account(
+        mut,
+        constraint = mint_config.game == game_state.key()
+            && bankroll.as_ref().is_some_and(|b| b.mint == mint_config.mint)
+            @ CasinoError::MintNotAccepted,
     )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub mint_config: Option<Account<'info, MintConfig>>,
 
-    /// Game escrow
+    /// Player's internal balance to bet from instead of the token account
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        constraint = player_balance.player == player.key()
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// Player profile PDA (stats, referrer and rakeback)
+    #[account(
+        init_if_needed,
+        payer = payer.as_ref().unwrap_or(&signer),
+        space = PlayerProfile::LEN,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Per-game bet throttle PDA (cooldown and bets per window)
+    #[account(
+        init_if_needed,
+        payer = payer.as_ref().unwrap_or(&signer),
+        space = PlayerThrottle::LEN,
+        seeds = [b"throttle", game_state.key().as_ref(), player.key().as_ref()],
+        bump
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub player_throttle: Box<Account<'info, PlayerThrottle>>,
+
+    /// VIP tier configuration (no rakeback when absent)
+    #[account(
+        constraint = bankroll.as_ref().is_some_and(|b| vip_config.bankroll == b.key()) @ CasinoError::WrongBankroll,
+    )]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    /// Referrer earnings PDA (required when the player has a referrer)
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
 
     /// System program
     pub system_program: Program<'info, System>,
 
-    /// Token program
-    pub token_program: Program<'info, Token>,
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 pub fn play_handler(
@@ -61,35 +165,77 @@ pub fn play_handler(
     let game = &ctx.accounts.game_state;
     let config = &game.config;
 
+    let funds = GameFunds::new(
+        &ctx.accounts.game_state,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.bankroll.as_ref(),
+        ctx.accounts.vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        &ctx.accounts.system_program,
+    );
+
+    // Bets outside the primary mint use the limits and ledger of their MintConfig
+    let bet_mint = funds.mint_key();
+    require!(
+        bet_mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+
     // Validate bet amount
-    require!(bet_amount >= config.min_bet, CasinoError::BetTooSmall);
-    require!(bet_amount <= config.max_bet, CasinoError::BetTooLarge);
+    let (min_bet, max_bet) = game.bet_limits(ctx.accounts.mint_config.as_deref());
+    require!(bet_amount >= min_bet, CasinoError::BetTooSmall);
+    require!(bet_amount <= max_bet, CasinoError::BetTooLarge);
+
+    // Session keys bet against the cap the player authorized, from the
+    // player's internal balance when staking tokens
+    let from_balance = ctx.accounts.player_balance.is_some();
+    authorize_bet(
+        ctx.accounts.session.as_deref_mut(),
+        ctx.accounts.player.key(),
+        ctx.accounts.signer.key(),
+        game.key(),
+        bet_mint,
+        bet_amount,
+        from_balance,
+    )?;
+
+    // Transfer bet to vault; the stake is what arrives after transfer fees.
+    // Bets from an internal balance are already in the vault.
+    let stake = if from_balance {
+        bet_amount
+    } else {
+        funds.collect(
+            &ctx.accounts.signer.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            bet_amount,
+        )?
+    };
 
-    // Calculate potential payout and check escrow
+    // Calculate potential payout (including outbound fees) and check vault
     let house_edge = config.house_edge_bps as u64;
     let multiplier = 20000 - (house_edge * 2); // 1.96x for 2% edge
-    let potential_payout = (bet_amount * multiplier) / 10000;
+    let potential_payout = funds.gross_up((stake * multiplier) / 10000)?;
     require!(
-        ctx.accounts.escrow.amount >= potential_payout,
+        funds.balance()? >= potential_payout,
         CasinoError::InsufficientEscrow
     );
+    require!(
+        potential_payout <= game.liquidity_for(ctx.accounts.mint_config.as_deref()),
+        CasinoError::AllocationExceeded
+    );
+    if let Some(balance) = ctx.accounts.player_balance.as_deref_mut() {
+        debit_balance(required(ctx.accounts.bankroll.as_deref_mut())?, balance, stake)?;
+    }
 
-    // Transfer bet to escrow
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.player_token_account.to_account_info(),
-        to: ctx.accounts.escrow.to_account_info(),
-        authority: ctx.accounts.player.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::transfer(cpi_ctx, bet_amount)?;
-
-    // Transfer platform fee (SOL)
+    // Transfer platform fee (SOL) from the relayer, or the signer without one
+    let payer = ctx.accounts.payer.as_ref().unwrap_or(&ctx.accounts.signer).to_account_info();
     let fee = config.platform_fee_lamports;
     anchor_lang::system_program::transfer(
         CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
             anchor_lang::system_program::Transfer {
-                from: ctx.accounts.player.to_account_info(),
+                from: payer.clone(),
                 to: ctx.accounts.game_state.to_account_info(),
             },
         ),
@@ -101,33 +247,79 @@ pub fn play_handler(
     let clock = Clock::get()?;
 
     bet.player = ctx.accounts.player.key();
+    bet.from_balance = from_balance;
+    bet.rent_payer = payer.key();
     bet.game = ctx.accounts.game_state.key();
     bet.round_number = 0;
-    bet.bet_amount = bet_amount;
+    bet.mint = bet_mint;
+    bet.bet_amount = stake;
     bet.fee_amount = fee;
     bet.bet_choice = choice as u8;
     bet.outcome = BetOutcome::Pending;
     bet.payout_amount = 0;
     bet.vrf_result = [0u8; 32];
     bet.bet_at = clock.unix_timestamp;
+    bet.bet_slot = clock.slot;
     bet.resolved_at = 0;
+    bet.multiplier_bps = multiplier as u32;
+    bet.version = PlayerBet::VERSION;
     bet.bump = ctx.bumps.player_bet;
 
     // Update game stats
     let game = &mut ctx.accounts.game_state;
-    game.total_volume = game.total_volume.checked_add(bet_amount).unwrap();
+    game.add_volume(ctx.accounts.mint_config.as_deref_mut(), stake);
     game.total_fees = game.total_fees.checked_add(fee).unwrap();
 
+    throttle_bet(
+        &ctx.accounts.game_state,
+        &mut ctx.accounts.player_throttle,
+        ctx.accounts.player.key(),
+        ctx.bumps.player_throttle,
+        clock.unix_timestamp,
+    )?;
+
+    let profile = &mut ctx.accounts.player_profile;
+    profile.ensure_initialized(
+        ctx.accounts.player.key(),
+        ctx.bumps.player_profile,
+        clock.unix_timestamp,
+    );
+    check_gaming_limits(
+        profile,
+        stake,
+        ctx.accounts.game_state.tracks_rewards(&bet_mint),
+        clock.unix_timestamp,
+    )?;
+
+    // Volume, rakeback and referral rewards are denominated in the primary bankroll token
+    if ctx.accounts.game_state.tracks_rewards(&bet_mint) {
+        record_wager(
+            &mut ctx.accounts.game_state,
+            required(ctx.accounts.bankroll.as_mut())?,
+            profile,
+            ctx.accounts.vip_config.as_ref(),
+            stake,
+            clock.unix_timestamp,
+        )?;
+        accrue_referral(
+            &mut ctx.accounts.game_state,
+            required(ctx.accounts.bankroll.as_mut())?,
+            &ctx.accounts.player_profile,
+            ctx.accounts.referrer_account.as_mut(),
+            stake,
+        )?;
+    }
+
     emit!(BetPlaced {
         game: ctx.accounts.game_state.key(),
         player: ctx.accounts.player.key(),
-        bet_amount,
+        bet_amount: stake,
         fee_amount: fee,
     });
 
     msg!(
         "Coin flip bet placed: {} tokens on {:?}",
-        bet_amount,
+        stake,
         choice
     );
 
@@ -136,11 +328,14 @@ pub fn play_handler(
 
 #[derive(Accounts)]
 pub struct ResolveCoinflip<'info> {
-    /// VRF authority (Switchboard callback)
+    /// VRF authority (Switchboard callback, game resolver)
     pub vrf_authority: Signer<'info>,
 
     /// Game state
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = game_state.resolver == vrf_authority.key() @ CasinoError::Unauthorized,
+    )]
     pub game_state: Account<'info, GameState>,
 
     /// Player bet to resolve
@@ -156,31 +351,100 @@ pub struct ResolveCoinflip<'info> {
     /// CHECK: Only used for key matching
     pub player: AccountInfo<'info>,
 
-    /// Player's token account
+    /// Player profile (stats)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
+        seeds = [b"profile", player.key().as_ref()],
+        bump = player_profile.bump,
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Player's token account (token games)
+    #[account(
+        mut,
+        associated_token::mint = player_bet.mint,
         associated_token::authority = player,
+        associated_token::token_program = token_program,
     )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Game escrow
+    /// Shared bankroll for the mint the bet was placed in (token games)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        seeds = [b"bankroll", player_bet.mint.as_ref()],
+        bump = bankroll.bump,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint the bet was placed in, SPL Token or Token-2022 (token games)
+    #[account(address = player_bet.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
-    /// Token program
-    pub token_program: Program<'info, Token>,
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = player_bet.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Limits and accounting for a non-primary mint (required when the bet used one)
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), player_bet.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Player's internal balance (required when the stake came from it)
+    #[account(
+        mut,
+        constraint = player_balance.player == player_bet.player
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
-pub fn resolve_handler(ctx: Context<ResolveCoinflip>, vrf_result: [u8; 32]) -> Result<()> {
+pub fn resolve_coinflip_handler(ctx: Context<ResolveCoinflip>, vrf_result: [u8; 32]) -> Result<()> {
+    let funds = GameFunds::new(
+        &ctx.accounts.game_state,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.bankroll.as_ref(),
+        ctx.accounts.vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        &ctx.accounts.system_program,
+    );
+
     let bet = &mut ctx.accounts.player_bet;
-    let game = &ctx.accounts.game_state;
+    let game = &mut ctx.accounts.game_state;
     let clock = Clock::get()?;
 
+    require!(
+        bet.mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+    require!(
+        !game.cancel_window_open(bet.bet_slot, clock.slot),
+        CasinoError::CancelWindowOpen
+    );
+
     // Determine result from VRF
     let result = calculate_coinflip_result(&vrf_result);
     let choice = if bet.bet_choice == 0 {
@@ -190,43 +454,45 @@ pub fn resolve_handler(ctx: Context<ResolveCoinflip>, vrf_result: [u8; 32]) -> R
     };
     let won = result == choice;
 
-    // Calculate payout
+    // Pay at the multiplier the bet was placed under, whatever the config is now
     let payout = if won {
-        let house_edge = game.config.house_edge_bps as u64;
-        let multiplier = 20000 - (house_edge * 2);
-        (bet.bet_amount * multiplier) / 10000
+        (bet.bet_amount * bet.multiplier_bps as u64) / 10000
     } else {
         0
     };
 
+    // The vault covers outbound transfer fees so the player receives the full
+    // payout; balance payouts never leave the vault
+    let gross_payout = if bet.from_balance {
+        payout
+    } else {
+        funds.gross_up(payout)?
+    };
+
     // Update bet record
     bet.outcome = if won { BetOutcome::Win } else { BetOutcome::Lose };
     bet.payout_amount = payout;
     bet.vrf_result = vrf_result;
     bet.resolved_at = clock.unix_timestamp;
+    game.settle(ctx.accounts.mint_config.as_deref_mut(), bet.bet_amount, gross_payout);
+    if game.tracks_rewards(&bet.mint) {
+        ctx.accounts.player_profile.record_result(bet.bet_amount, payout);
+    }
 
     // Pay out if won
-    if payout > 0 {
-        let slug = game.slug_as_str();
-        let seeds = &[
-            b"game".as_ref(),
-            slug.as_bytes(),
-            &[game.escrow_bump],
-        ];
-        let signer_seeds = &[&seeds[..]];
-
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.escrow.to_account_info(),
-            to: ctx.accounts.player_token_account.to_account_info(),
-            authority: ctx.accounts.game_state.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            signer_seeds,
-        );
-        token::transfer(cpi_ctx, payout)?;
+    if payout > 0 && bet.from_balance {
+        require!(funds.balance()? >= gross_payout, CasinoError::InsufficientEscrow);
+        credit_balance(
+            required(ctx.accounts.bankroll.as_deref_mut())?,
+            required(ctx.accounts.player_balance.as_deref_mut())?,
+            payout,
+        )?;
+    } else if payout > 0 {
+        funds.pay(
+            &ctx.accounts.player,
+            ctx.accounts.player_token_account.as_ref(),
+            gross_payout,
+        )?;
     }
 
     emit!(BetResolved {
@@ -0,0 +1,149 @@
+//! Timelocked game config instructions
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::{CasinoError, ConfigApplied, ConfigProposed};
+
+#[derive(Accounts)]
+pub struct ProposeConfig<'info> {
+    /// Game authority (pays for the proposal)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(has_one = authority @ CasinoError::Unauthorized)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Config proposal PDA (a new proposal replaces a pending one)
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ConfigProposal::LEN,
+        seeds = [b"config", game_state.key().as_ref()],
+        bump
+    )]
+    pub config_proposal: Account<'info, ConfigProposal>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_config_handler(
+    ctx: Context<ProposeConfig>,
+    config: GameConfig,
+    config_delay: i64,
+) -> Result<()> {
+    propose_config(
+        &ctx.accounts.game_state,
+        &mut ctx.accounts.config_proposal,
+        ctx.bumps.config_proposal,
+        config,
+        config_delay,
+    )
+}
+
+/// Reject configs the game type's payout math can't handle
+pub fn validate_config(game_type: GameType, config: &GameConfig) -> Result<()> {
+    match game_type {
+        // min_bet is the ticket price, divided into the pool on draw
+        GameType::Jackpot => require!(config.min_bet > 0, CasinoError::ZeroTicketPrice),
+        _ => require!(config.min_bet > 0, CasinoError::ZeroMinBet),
+    }
+    require!(config.min_bet <= config.max_bet, CasinoError::InvalidBetLimits);
+
+    match game_type {
+        // Payout multiplier is 20000 - 2 * edge and must stay above 1x
+        GameType::CoinFlip => {
+            require!(config.house_edge_bps < 5000, CasinoError::CoinflipEdgeTooHigh)
+        }
+        _ => require!(config.house_edge_bps < 10000, CasinoError::InvalidHouseEdge),
+    }
+
+    Ok(())
+}
+
+/// Validate and record a config proposal for a game
+pub fn propose_config(
+    game: &Account<GameState>,
+    proposal: &mut Account<ConfigProposal>,
+    bump: u8,
+    config: GameConfig,
+    config_delay: i64,
+) -> Result<()> {
+    validate_config(game.game_type, &config)?;
+    require!(config_delay >= 0, CasinoError::InvalidConfigDelay);
+
+    let clock = Clock::get()?;
+
+    // The current delay applies, including to a change of the delay itself
+    proposal.game = game.key();
+    proposal.config = config;
+    proposal.config_delay = config_delay;
+    proposal.proposed_at = clock.unix_timestamp;
+    proposal.effective_at = clock.unix_timestamp.checked_add(game.config_delay).unwrap();
+    proposal.version = ConfigProposal::VERSION;
+    proposal.bump = bump;
+
+    emit!(ConfigProposed {
+        game: game.key(),
+        config,
+        config_delay,
+        effective_at: proposal.effective_at,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApplyConfig<'info> {
+    /// Game authority (receives the proposal rent)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Pending proposal (closed once applied)
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"config", game_state.key().as_ref()],
+        bump = config_proposal.bump,
+    )]
+    pub config_proposal: Account<'info, ConfigProposal>,
+}
+
+pub fn apply_handler(ctx: Context<ApplyConfig>) -> Result<()> {
+    apply_config(&mut ctx.accounts.game_state, &ctx.accounts.config_proposal)
+}
+
+/// Apply a config proposal whose delay has passed
+pub fn apply_config(game: &mut Account<GameState>, proposal: &ConfigProposal) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // A council may pass a proposal account created just now, left zeroed
+    require!(proposal.game == game.key(), CasinoError::NoPendingConfig);
+    validate_config(game.game_type, &proposal.config)?;
+    require!(
+        clock.unix_timestamp >= proposal.effective_at,
+        CasinoError::ConfigLocked
+    );
+
+    game.config = proposal.config;
+    game.config_delay = proposal.config_delay;
+
+    emit!(ConfigApplied {
+        game: game.key(),
+        config: game.config,
+        config_delay: game.config_delay,
+    });
+
+    msg!("Config applied to {}", game.slug_as_str());
+
+    Ok(())
+}
@@ -0,0 +1,407 @@
+//! Council (M-of-N multisig) instructions for privileged game actions
+
+use anchor_lang::prelude::*;
+
+use crate::instructions::authority::{accept_authority, propose_authority};
+use crate::instructions::burn::set_burn_share;
+use crate::instructions::cancel::set_cancel_policy;
+use crate::instructions::config::{apply_config, propose_config};
+use crate::instructions::crash::advance_crash_round;
+use crate::instructions::mint_config::{add_game_mint, set_mint_limits};
+use crate::instructions::native::{fund_sol_vault, withdraw_sol_vault};
+use crate::instructions::pause::{pause_game, resume_game};
+use crate::instructions::refund::set_resolution_timeout;
+use crate::instructions::roles::assign_role;
+use crate::instructions::throttle::set_bet_throttle;
+use crate::instructions::withdraw::withdraw_fees;
+use crate::state::*;
+use crate::transfer::required;
+use crate::{
+    CasinoError, CouncilCreated, CouncilProposalApproved, CouncilProposalCreated,
+    CouncilProposalExecuted,
+};
+
+#[derive(Accounts)]
+#[instruction(create_key: Pubkey)]
+pub struct CreateCouncil<'info> {
+    /// Payer for the council account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Council PDA
+    #[account(
+        init,
+        payer = payer,
+        space = Council::LEN,
+        seeds = [b"council", create_key.as_ref()],
+        bump
+    )]
+    pub council: Account<'info, Council>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_council_handler(
+    ctx: Context<CreateCouncil>,
+    create_key: Pubkey,
+    members: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    require!(
+        !members.is_empty() && members.len() <= MAX_COUNCIL_MEMBERS,
+        CasinoError::InvalidCouncil
+    );
+    require!(
+        threshold > 0 && threshold as usize <= members.len(),
+        CasinoError::InvalidCouncil
+    );
+    for (i, member) in members.iter().enumerate() {
+        require!(!members[..i].contains(member), CasinoError::InvalidCouncil);
+    }
+
+    let council = &mut ctx.accounts.council;
+    council.create_key = create_key;
+    council.members[..members.len()].copy_from_slice(&members);
+    council.member_count = members.len() as u8;
+    council.threshold = threshold;
+    council.proposal_count = 0;
+    council.version = Council::VERSION;
+    council.bump = ctx.bumps.council;
+
+    emit!(CouncilCreated {
+        council: council.key(),
+        members,
+        threshold,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateCouncilProposal<'info> {
+    /// Proposing member (pays for the proposal)
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    /// Council
+    #[account(
+        mut,
+        seeds = [b"council", council.create_key.as_ref()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, Council>,
+
+    /// Proposal PDA
+    #[account(
+        init,
+        payer = member,
+        space = CouncilProposal::LEN,
+        seeds = [b"council_proposal", council.key().as_ref(), &council.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+
+    /// Game the action targets
+    pub game_state: Account<'info, GameState>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_council_proposal_handler(ctx: Context<CreateCouncilProposal>, action: CouncilAction) -> Result<()> {
+    let council = &mut ctx.accounts.council;
+    let game = &ctx.accounts.game_state;
+
+    let member_index = council
+        .member_index(&ctx.accounts.member.key())
+        .ok_or(CasinoError::NotCouncilMember)?;
+
+    require!(
+        controls_game(game, &council.key(), &action),
+        CasinoError::Unauthorized
+    );
+
+    // The proposer approves implicitly
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.council = council.key();
+    proposal.index = council.proposal_count;
+    proposal.game = game.key();
+    proposal.action = action;
+    proposal.proposer = ctx.accounts.member.key();
+    proposal.approvals = 1 << member_index;
+    proposal.executed = false;
+    proposal.created_at = Clock::get()?.unix_timestamp;
+    proposal.version = CouncilProposal::VERSION;
+    proposal.bump = ctx.bumps.proposal;
+
+    council.proposal_count = council.proposal_count.checked_add(1).unwrap();
+
+    emit!(CouncilProposalCreated {
+        council: proposal.council,
+        proposal: proposal.key(),
+        game: proposal.game,
+        proposer: proposal.proposer,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApproveCouncilProposal<'info> {
+    /// Approving member
+    pub member: Signer<'info>,
+
+    /// Council
+    #[account(
+        seeds = [b"council", council.create_key.as_ref()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, Council>,
+
+    /// Proposal to approve
+    #[account(
+        mut,
+        has_one = council,
+        constraint = !proposal.executed @ CasinoError::ProposalExecuted,
+        seeds = [b"council_proposal", council.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+}
+
+pub fn approve_handler(ctx: Context<ApproveCouncilProposal>) -> Result<()> {
+    let member_index = ctx
+        .accounts
+        .council
+        .member_index(&ctx.accounts.member.key())
+        .ok_or(CasinoError::NotCouncilMember)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    let bit = 1u16 << member_index;
+    require!(proposal.approvals & bit == 0, CasinoError::AlreadyApproved);
+    proposal.approvals |= bit;
+
+    emit!(CouncilProposalApproved {
+        proposal: proposal.key(),
+        member: ctx.accounts.member.key(),
+        approvals: proposal.approval_count(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCouncilProposal<'info> {
+    /// Executing member (pays for a config proposal or mint config, receives
+    /// the config proposal's rent on apply, funds SOL vault deposits)
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    /// Council
+    #[account(
+        seeds = [b"council", council.create_key.as_ref()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, Council>,
+
+    /// Approved proposal
+    #[account(
+        mut,
+        has_one = council,
+        constraint = !proposal.executed @ CasinoError::ProposalExecuted,
+        seeds = [b"council_proposal", council.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+
+    /// Target game
+    #[account(
+        mut,
+        address = proposal.game,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Game config proposal (config actions)
+    #[account(
+        init_if_needed,
+        payer = member,
+        space = ConfigProposal::LEN,
+        seeds = [b"config", game_state.key().as_ref()],
+        bump
+    )]
+    pub config_proposal: Option<Account<'info, ConfigProposal>>,
+
+    /// Bankroll for the mint being added or limited (mint actions)
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint config for the bankroll's mint (mint actions)
+    #[account(
+        init_if_needed,
+        payer = member,
+        space = MintConfig::LEN,
+        seeds = [
+            b"mint_config",
+            game_state.key().as_ref(),
+            bankroll.as_ref().map(|b| b.mint).unwrap_or_default().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Native SOL vault (SOL vault actions)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Fee or SOL vault withdrawal recipient (withdrawals)
+    /// CHECK: Must match the recipient named in the proposal
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
+
+    /// Current crash round (round actions)
+    #[account(
+        mut,
+        seeds = [b"round", game_state.key().as_ref(), &game_state.current_round.to_le_bytes()],
+        bump = round_state.bump,
+    )]
+    pub round_state: Option<Account<'info, RoundState>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn execute_council_proposal_handler(ctx: Context<ExecuteCouncilProposal>) -> Result<()> {
+    let council = &ctx.accounts.council;
+    require!(
+        council.member_index(&ctx.accounts.member.key()).is_some(),
+        CasinoError::NotCouncilMember
+    );
+
+    let proposal = &ctx.accounts.proposal;
+    require!(
+        proposal.approval_count() >= council.threshold,
+        CasinoError::ThresholdNotMet
+    );
+
+    let game = &mut ctx.accounts.game_state;
+    require!(
+        controls_game(game, &council.key(), &proposal.action),
+        CasinoError::Unauthorized
+    );
+
+    match proposal.action {
+        CouncilAction::WithdrawFees { amount, recipient } => {
+            let to = required(ctx.accounts.recipient.as_ref())?;
+            require!(to.key() == recipient, CasinoError::WrongRecipient);
+            withdraw_fees(game, &to.to_account_info(), amount)?;
+        }
+        CouncilAction::ProposeConfig {
+            config,
+            config_delay,
+        } => {
+            let config_proposal = required(ctx.accounts.config_proposal.as_mut())?;
+            propose_config(
+                game,
+                config_proposal,
+                ctx.bumps.config_proposal,
+                config,
+                config_delay,
+            )?;
+        }
+        CouncilAction::ApplyConfig => {
+            let config_proposal = required(ctx.accounts.config_proposal.as_ref())?;
+            apply_config(game, config_proposal)?;
+            config_proposal.close(ctx.accounts.member.to_account_info())?;
+        }
+        CouncilAction::PauseGame => pause_game(game)?,
+        CouncilAction::ResumeGame => resume_game(game)?,
+        CouncilAction::ProposeAuthority { new_authority } => {
+            propose_authority(game, new_authority)?
+        }
+        CouncilAction::AcceptAuthority => accept_authority(game)?,
+        CouncilAction::AssignRole { role, holder } => assign_role(game, role, holder)?,
+        CouncilAction::SetBurnShare { burn_bps } => set_burn_share(game, burn_bps)?,
+        CouncilAction::AddGameMint { min_bet, max_bet } => add_game_mint(
+            game,
+            required(ctx.accounts.bankroll.as_mut())?,
+            required(ctx.accounts.mint_config.as_mut())?,
+            ctx.bumps.mint_config,
+            min_bet,
+            max_bet,
+        )?,
+        CouncilAction::SetMintLimits { min_bet, max_bet } => {
+            let mint_config = required(ctx.accounts.mint_config.as_mut())?;
+            require!(game.accepts_mint(&mint_config.mint), CasinoError::MintNotAccepted);
+            set_mint_limits(mint_config, min_bet, max_bet)?;
+        }
+        CouncilAction::SetResolutionTimeout { slots } => set_resolution_timeout(game, slots)?,
+        CouncilAction::SetBetThrottle {
+            bet_window_seconds,
+            max_bets_per_window,
+        } => set_bet_throttle(game, bet_window_seconds, max_bets_per_window)?,
+        CouncilAction::SetCancelPolicy {
+            cancel_window_slots,
+            cancel_fee_bps,
+        } => set_cancel_policy(game, cancel_window_slots, cancel_fee_bps)?,
+        CouncilAction::FundSolVault { amount } => fund_sol_vault(
+            game,
+            required(ctx.accounts.sol_vault.as_ref())?,
+            &ctx.accounts.member.to_account_info(),
+            &ctx.accounts.system_program,
+            amount,
+        )?,
+        CouncilAction::WithdrawSolVault { amount, recipient } => {
+            let to = required(ctx.accounts.recipient.as_ref())?;
+            require!(to.key() == recipient, CasinoError::WrongRecipient);
+            withdraw_sol_vault(
+                game,
+                required(ctx.accounts.sol_vault.as_ref())?,
+                &to.to_account_info(),
+                &ctx.accounts.system_program,
+                amount,
+            )?;
+        }
+        CouncilAction::AdvanceCrashRound => {
+            advance_crash_round(game, required(ctx.accounts.round_state.as_mut())?)?
+        }
+    }
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.executed = true;
+
+    emit!(CouncilProposalExecuted {
+        council: proposal.council,
+        proposal: proposal.key(),
+        game: proposal.game,
+    });
+
+    Ok(())
+}
+
+/// Whether the council holds the role `action` requires: treasurer for fee
+/// withdrawals and SOL vault liquidity, authority or operator for pausing
+/// and advancing crash rounds, the nominated authority for accepting a transfer, and the authority for
+/// everything else
+fn controls_game(game: &GameState, council: &Pubkey, action: &CouncilAction) -> bool {
+    match action {
+        CouncilAction::WithdrawFees { .. }
+        | CouncilAction::FundSolVault { .. }
+        | CouncilAction::WithdrawSolVault { .. } => game.treasurer == *council,
+        CouncilAction::PauseGame
+        | CouncilAction::ResumeGame
+        | CouncilAction::AdvanceCrashRound => {
+            game.authority == *council || game.operator == *council
+        }
+        CouncilAction::AcceptAuthority => game.pending_authority == *council,
+        _ => game.authority == *council,
+    }
+}
@@ -1,21 +1,28 @@
 //! Crash game instructions
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::state::*;
-use crate::{RoundStarted, RoundEnded, BetPlaced, CashoutEvent, CasinoError};
+use crate::transfer::{required, GameFunds};
+use crate::instructions::balance::debit_balance;
+use crate::instructions::profile::record_wager;
+use crate::instructions::referral::accrue_referral;
+use crate::instructions::responsible::check_gaming_limits;
+use crate::instructions::session::authorize_bet;
+use crate::instructions::throttle::throttle_bet;
+use crate::{RoundStarted, RoundActive, RoundEnded, BetPlaced, CashoutEvent, CasinoError};
 
 #[derive(Accounts)]
 pub struct StartCrashRound<'info> {
-    /// Authority
+    /// Game operator
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub operator: Signer<'info>,
 
     /// Game state
     #[account(
         mut,
-        has_one = authority @ CasinoError::Unauthorized,
+        has_one = operator @ CasinoError::Unauthorized,
         constraint = game_state.game_type == GameType::Crash @ CasinoError::GameNotActive,
     )]
     pub game_state: Account<'info, GameState>,
@@ -23,7 +30,7 @@ pub struct StartCrashRound<'info> {
     /// Round state PDA
     #[account(
         init,
-        payer = authority,
+        payer = operator,
         space = RoundState::LEN,
         seeds = [b"round", game_state.key().as_ref(), &(game_state.current_round + 1).to_le_bytes()],
         bump
@@ -49,8 +56,11 @@ pub fn start_round_handler(ctx: Context<StartCrashRound>) -> Result<()> {
     round.vrf_result = [0u8; 32];
     round.result = [0u8; 32];
     round.started_at = clock.unix_timestamp;
+    round.started_slot = clock.slot;
     round.betting_ends_at = clock.unix_timestamp + 10; // 10 second betting phase
     round.ended_at = 0;
+    round.rent_payer = ctx.accounts.operator.key();
+    round.version = RoundState::VERSION;
     round.bump = ctx.bumps.round_state;
 
     emit!(RoundStarted {
@@ -61,18 +71,85 @@ pub fn start_round_handler(ctx: Context<StartCrashRound>) -> Result<()> {
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct AdvanceCrashRound<'info> {
+    /// Game operator
+    pub operator: Signer<'info>,
+
+    /// Game state
+    #[account(has_one = operator @ CasinoError::Unauthorized)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Current round
+    #[account(
+        mut,
+        seeds = [b"round", game_state.key().as_ref(), &game_state.current_round.to_le_bytes()],
+        bump = round_state.bump,
+    )]
+    pub round_state: Account<'info, RoundState>,
+}
+
+pub fn advance_round_handler(ctx: Context<AdvanceCrashRound>) -> Result<()> {
+    advance_crash_round(&ctx.accounts.game_state, &mut ctx.accounts.round_state)
+}
+
+/// Move a crash round from betting to active once its betting window has
+/// passed, which opens it for cashouts
+pub fn advance_crash_round(game: &Account<GameState>, round: &mut Account<RoundState>) -> Result<()> {
+    require!(game.game_type == GameType::Crash, CasinoError::GameNotActive);
+    require!(round.phase == RoundPhase::Betting, CasinoError::RoundNotBetting);
+    require!(
+        Clock::get()?.unix_timestamp >= round.betting_ends_at,
+        CasinoError::BettingStillOpen
+    );
+
+    round.phase = RoundPhase::Active;
+
+    emit!(RoundActive {
+        game: game.key(),
+        round_number: round.round_number,
+    });
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct JoinCrash<'info> {
-    /// Player
+    /// Wallet or session key signing the bet (native SOL stakes come from it)
     #[account(mut)]
-    pub player: Signer<'info>,
+    pub signer: Signer<'info>,
+
+    /// Player wallet (owns the bet; payouts go to it)
+    /// CHECK: Must be `signer` unless `session` authorizes it, see `authorize_bet`
+    pub player: UncheckedAccount<'info>,
+
+    /// Session authorizing `signer` to bet for `player`
+    #[account(
+        mut,
+        seeds = [b"session", player.key().as_ref(), signer.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, Session>>,
+
+    /// Relayer paying rent and platform fees (the signer pays when omitted)
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
 
     /// Game state
     #[account(
+        mut,
         constraint = game_state.is_active @ CasinoError::GameNotActive,
     )]
     pub game_state: Account<'info, GameState>,
 
+    /// Global config (bets are refused during an emergency stop)
+    #[account(
+        seeds = [b"global"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ CasinoError::EmergencyStop,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     /// Current round
     #[account(
         mut,
@@ -85,87 +162,273 @@ pub struct JoinCrash<'info> {
     /// Participant PDA
     #[account(
         init,
-        payer = player,
+        payer = payer.as_ref().unwrap_or(&signer),
         space = RoundParticipant::LEN,
         seeds = [b"participant", round_state.key().as_ref(), player.key().as_ref()],
         bump
     )]
     pub participant: Account<'info, RoundParticipant>,
 
-    /// Player's token account
+    /// Player's token account (token games)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
+        associated_token::mint = mint,
         associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint being bet (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+        constraint = game_state.accepts_mint(&bankroll.mint) @ CasinoError::MintNotAccepted,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint being bet, SPL Token or Token-2022 (token games)
+    #[account(
+        constraint = bankroll.as_ref().is_some_and(|b| b.mint == mint.key()) @ CasinoError::WrongBankroll,
+    )]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
     )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub sol_vault: Option<SystemAccount<'info>>,
 
-    /// Escrow
+    /// Limits and accounting for a non-primary mint (required when betting in one)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        constraint = mint_config.game == game_state.key()
+            && bankroll.as_ref().is_some_and(|b| b.mint == mint_config.mint)
+            @ CasinoError::MintNotAccepted,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Player's internal balance to bet from instead of the token account
+    #[account(
+        mut,
+        constraint = player_balance.player == player.key()
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// Player profile PDA (stats, referrer and rakeback)
+    #[account(
+        init_if_needed,
+        payer = payer.as_ref().unwrap_or(&signer),
+        space = PlayerProfile::LEN,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Per-game bet throttle PDA (cooldown and bets per window)
+    #[account(
+        init_if_needed,
+        payer = payer.as_ref().unwrap_or(&signer),
+        space = PlayerThrottle::LEN,
+        seeds = [b"throttle", game_state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_throttle: Box<Account<'info, PlayerThrottle>>,
+
+    /// VIP tier configuration (no rakeback when absent)
+    #[account(
+        constraint = bankroll.as_ref().is_some_and(|b| vip_config.bankroll == b.key()) @ CasinoError::WrongBankroll,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    /// Referrer earnings PDA (required when the player has a referrer)
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
 
     /// System program
     pub system_program: Program<'info, System>,
 
-    /// Token program
-    pub token_program: Program<'info, Token>,
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 pub fn join_handler(ctx: Context<JoinCrash>, bet_amount: u64) -> Result<()> {
+    let funds = GameFunds::new(
+        &ctx.accounts.game_state,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.bankroll.as_ref(),
+        ctx.accounts.vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        &ctx.accounts.system_program,
+    );
+
     let game = &ctx.accounts.game_state;
     let round = &mut ctx.accounts.round_state;
     let participant = &mut ctx.accounts.participant;
     let clock = Clock::get()?;
 
+    // Bets outside the primary mint use the limits and ledger of their MintConfig
+    let bet_mint = funds.mint_key();
+    require!(
+        bet_mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+
     // Validate bet
-    require!(bet_amount >= game.config.min_bet, CasinoError::BetTooSmall);
-    require!(bet_amount <= game.config.max_bet, CasinoError::BetTooLarge);
-
-    // Transfer bet
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.player_token_account.to_account_info(),
-        to: ctx.accounts.escrow.to_account_info(),
-        authority: ctx.accounts.player.to_account_info(),
+    let (min_bet, max_bet) = game.bet_limits(ctx.accounts.mint_config.as_deref());
+    require!(bet_amount >= min_bet, CasinoError::BetTooSmall);
+    require!(bet_amount <= max_bet, CasinoError::BetTooLarge);
+
+    // Session keys bet against the cap the player authorized
+    authorize_bet(
+        ctx.accounts.session.as_deref_mut(),
+        ctx.accounts.player.key(),
+        ctx.accounts.signer.key(),
+        game.key(),
+        bet_mint,
+        bet_amount,
+        ctx.accounts.player_balance.is_some(),
+    )?;
+
+    // Transfer bet; the stake is what arrives after transfer fees.
+    // Bets from an internal balance are already in the vault.
+    let stake = match ctx.accounts.player_balance.as_deref_mut() {
+        Some(balance) => {
+            debit_balance(required(ctx.accounts.bankroll.as_deref_mut())?, balance, bet_amount)?;
+            bet_amount
+        }
+        None => funds.collect(
+            &ctx.accounts.signer.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            bet_amount,
+        )?,
     };
-    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::transfer(cpi_ctx, bet_amount)?;
+
+    // Transfer platform fee (SOL) from the relayer, or the signer without one
+    let payer = ctx.accounts.payer.as_ref().unwrap_or(&ctx.accounts.signer).to_account_info();
+    let fee = game.config.platform_fee_lamports;
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.clone(),
+                to: ctx.accounts.game_state.to_account_info(),
+            },
+        ),
+        fee,
+    )?;
 
     // Initialize participant
     participant.player = ctx.accounts.player.key();
     participant.round = round.key();
-    participant.bet_amount = bet_amount;
+    participant.mint = bet_mint;
+    participant.bet_amount = stake;
+    participant.fee_amount = fee;
     participant.cashed_out = false;
     participant.cashout_multiplier = 0;
     participant.payout = 0;
     participant.joined_at = clock.unix_timestamp;
     participant.cashed_out_at = 0;
+    participant.from_balance = ctx.accounts.player_balance.is_some();
+    participant.rent_payer = payer.key();
+    participant.version = RoundParticipant::VERSION;
     participant.bump = ctx.bumps.participant;
 
-    // Update round
-    round.pool_size = round.pool_size.checked_add(bet_amount).unwrap();
+    // Update round. Primary-mint stakes are realized when the round ends;
+    // stakes in other mints are booked to their MintConfig ledger right away.
+    match ctx.accounts.mint_config.as_deref_mut() {
+        Some(mint_config) => mint_config.record_settlement(stake, 0),
+        None => round.pool_size = round.pool_size.checked_add(stake).unwrap(),
+    }
     round.participant_count += 1;
 
+    let game = &mut ctx.accounts.game_state;
+    game.total_fees = game.total_fees.checked_add(fee).unwrap();
+
     emit!(BetPlaced {
         game: game.key(),
         player: ctx.accounts.player.key(),
-        bet_amount,
-        fee_amount: game.config.platform_fee_lamports,
+        bet_amount: stake,
+        fee_amount: fee,
     });
 
+    throttle_bet(
+        &ctx.accounts.game_state,
+        &mut ctx.accounts.player_throttle,
+        ctx.accounts.player.key(),
+        ctx.bumps.player_throttle,
+        clock.unix_timestamp,
+    )?;
+
+    let profile = &mut ctx.accounts.player_profile;
+    profile.ensure_initialized(
+        ctx.accounts.player.key(),
+        ctx.bumps.player_profile,
+        clock.unix_timestamp,
+    );
+    check_gaming_limits(
+        profile,
+        stake,
+        ctx.accounts.game_state.tracks_rewards(&bet_mint),
+        clock.unix_timestamp,
+    )?;
+
+    // Volume, rakeback and referral rewards are denominated in the primary bankroll token
+    if ctx.accounts.game_state.tracks_rewards(&bet_mint) {
+        record_wager(
+            &mut ctx.accounts.game_state,
+            required(ctx.accounts.bankroll.as_mut())?,
+            profile,
+            ctx.accounts.vip_config.as_ref(),
+            stake,
+            clock.unix_timestamp,
+        )?;
+        accrue_referral(
+            &mut ctx.accounts.game_state,
+            required(ctx.accounts.bankroll.as_mut())?,
+            &ctx.accounts.player_profile,
+            ctx.accounts.referrer_account.as_mut(),
+            stake,
+        )?;
+    }
+
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct CashoutCrash<'info> {
-    /// Player
+    /// Wallet or session key signing the cashout
+    pub signer: Signer<'info>,
+
+    /// Player wallet (owns the entry; the payout goes to it)
+    /// CHECK: Must be `signer` unless `session` authorizes it, see `authorize_bet`
     #[account(mut)]
-    pub player: Signer<'info>,
+    pub player: UncheckedAccount<'info>,
+
+    /// Session authorizing `signer` to bet for `player`
+    #[account(
+        mut,
+        seeds = [b"session", player.key().as_ref(), signer.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, Session>>,
 
     /// Game state
+    #[account(mut)]
     pub game_state: Account<'info, GameState>,
 
     /// Current round
@@ -185,24 +448,64 @@ pub struct CashoutCrash<'info> {
     )]
     pub participant: Account<'info, RoundParticipant>,
 
-    /// Player's token account
+    /// Player profile (stats)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
+        seeds = [b"profile", player.key().as_ref()],
+        bump = player_profile.bump,
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Player's token account (token games)
+    #[account(
+        mut,
+        associated_token::mint = participant.mint,
         associated_token::authority = player,
+        associated_token::token_program = token_program,
     )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Escrow
+    /// Shared bankroll for the mint the bet was placed in (token games)
+    #[account(
+        seeds = [b"bankroll", participant.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint the bet was placed in, SPL Token or Token-2022 (token games)
+    #[account(address = participant.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        associated_token::mint = participant.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Token program
-    pub token_program: Program<'info, Token>,
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Limits and accounting for a non-primary mint (required when the bet used one)
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), participant.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 
     /// Current multiplier oracle (simplified - would use Switchboard in production)
     /// CHECK: Read-only oracle data
@@ -210,17 +513,48 @@ pub struct CashoutCrash<'info> {
 }
 
 pub fn cashout_handler(ctx: Context<CashoutCrash>) -> Result<()> {
+    let funds = GameFunds::new(
+        &ctx.accounts.game_state,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.bankroll.as_ref(),
+        ctx.accounts.vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        &ctx.accounts.system_program,
+    );
+
     let game = &ctx.accounts.game_state;
     let participant = &mut ctx.accounts.participant;
     let clock = Clock::get()?;
 
+    require!(
+        participant.mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+
+    // Session keys may cash out any entry their scope covers
+    authorize_bet(
+        ctx.accounts.session.as_deref_mut(),
+        ctx.accounts.player.key(),
+        ctx.accounts.signer.key(),
+        game.key(),
+        participant.mint,
+        0,
+        false,
+    )?;
+
     // Get current multiplier (simplified - in production, read from oracle/clock-based calculation)
     // For now, use time elapsed as multiplier basis
     let elapsed = clock.unix_timestamp - ctx.accounts.round_state.started_at;
     let multiplier = 10000 + (elapsed as u32 * 100); // 1.00x + 0.01x per second
 
-    // Calculate payout
+    // Calculate payout (the vault covers outbound transfer fees)
     let payout = (participant.bet_amount * multiplier as u64) / 10000;
+    let gross_payout = funds.gross_up(payout)?;
+    require!(
+        gross_payout <= game.liquidity_for(ctx.accounts.mint_config.as_deref()),
+        CasinoError::AllocationExceeded
+    );
 
     // Update participant
     participant.cashed_out = true;
@@ -229,24 +563,20 @@ pub fn cashout_handler(ctx: Context<CashoutCrash>) -> Result<()> {
     participant.cashed_out_at = clock.unix_timestamp;
 
     // Transfer payout
-    let slug = game.slug_as_str();
-    let seeds = &[b"game".as_ref(), slug.as_bytes(), &[game.escrow_bump]];
-    let signer_seeds = &[&seeds[..]];
-
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.escrow.to_account_info(),
-        to: ctx.accounts.player_token_account.to_account_info(),
-        authority: ctx.accounts.game_state.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        cpi_accounts,
-        signer_seeds,
-    );
-    token::transfer(cpi_ctx, payout)?;
+    funds.pay(
+        &ctx.accounts.player.to_account_info(),
+        ctx.accounts.player_token_account.as_ref(),
+        gross_payout,
+    )?;
+
+    let game = &mut ctx.accounts.game_state;
+    game.settle(ctx.accounts.mint_config.as_deref_mut(), 0, gross_payout);
+    if game.tracks_rewards(&participant.mint) {
+        ctx.accounts.player_profile.record_result(participant.bet_amount, payout);
+    }
 
     emit!(CashoutEvent {
-        game: game.key(),
+        game: ctx.accounts.game_state.key(),
         player: ctx.accounts.player.key(),
         multiplier,
         payout,
@@ -257,11 +587,14 @@ pub fn cashout_handler(ctx: Context<CashoutCrash>) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct ResolveCrash<'info> {
-    /// VRF authority
+    /// VRF authority (game resolver)
     pub vrf_authority: Signer<'info>,
 
     /// Game state
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = game_state.resolver == vrf_authority.key() @ CasinoError::Unauthorized,
+    )]
     pub game_state: Account<'info, GameState>,
 
     /// Round to resolve
@@ -274,7 +607,7 @@ pub struct ResolveCrash<'info> {
     pub round_state: Account<'info, RoundState>,
 }
 
-pub fn resolve_handler(ctx: Context<ResolveCrash>, vrf_result: [u8; 32]) -> Result<()> {
+pub fn resolve_crash_handler(ctx: Context<ResolveCrash>, vrf_result: [u8; 32]) -> Result<()> {
     let round = &mut ctx.accounts.round_state;
     let clock = Clock::get()?;
 
@@ -287,6 +620,9 @@ pub fn resolve_handler(ctx: Context<ResolveCrash>, vrf_result: [u8; 32]) -> Resu
     round.phase = RoundPhase::Ended;
     round.ended_at = clock.unix_timestamp;
 
+    // Stakes are realized once the round ends; cashouts were booked as they were paid
+    ctx.accounts.game_state.record_settlement(round.pool_size, 0);
+
     emit!(RoundEnded {
         game: ctx.accounts.game_state.key(),
         round_number: round.round_number,
@@ -1,60 +1,239 @@
-//! Fund pool instruction
+//! Fund pool instruction (community liquidity deposits)
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface};
+use anchor_spl::associated_token::AssociatedToken;
 
 use crate::state::*;
-use crate::CasinoError;
+use crate::transfer::transfer_to_vault;
+use crate::{CasinoError, LiquidityDeposited, SharesSeeded};
 
 #[derive(Accounts)]
 pub struct FundPool<'info> {
-    /// Authority (must match game authority)
+    /// Depositor (any $CC holder)
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub depositor: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
 
-    /// Game state
+    /// Depositor's token account
     #[account(
         mut,
-        has_one = authority @ CasinoError::Unauthorized,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program,
     )]
-    pub game_state: Account<'info, GameState>,
+    pub depositor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bankroll token mint
+    #[account(address = bankroll.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// Authority's token account
+    /// Shared vault token account
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = authority,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
     )]
-    pub authority_token_account: Account<'info, TokenAccount>,
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Game escrow token account
+    /// Liquidity share mint
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        address = bankroll.share_mint,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Depositor's share token account (receives minted shares)
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        associated_token::mint = share_mint,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program,
+    )]
+    pub depositor_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
 
     /// Token program
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn handler(ctx: Context<FundPool>, amount: u64) -> Result<()> {
-    // Transfer tokens to escrow
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.authority_token_account.to_account_info(),
-        to: ctx.accounts.escrow.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-    };
+pub fn fund_pool_handler(ctx: Context<FundPool>, amount: u64) -> Result<()> {
+    let bankroll = &ctx.accounts.bankroll;
+
+    // Transfer tokens to the shared vault
+    let received = transfer_to_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.depositor_token_account,
+        &ctx.accounts.vault,
+        &ctx.accounts.depositor.to_account_info(),
+        amount,
+    )?;
 
-    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
+    // Price shares for what actually arrived, against the vault before this deposit
+    let shares = deposit_shares(
+        bankroll,
+        &ctx.accounts.vault,
+        &ctx.accounts.share_mint,
+        received,
+    )?;
+
+    // Mint shares to the depositor
+    mint_shares(
+        bankroll,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.depositor_share_account,
+        &ctx.accounts.token_program,
+        shares,
+    )?;
+
+    emit!(LiquidityDeposited {
+        bankroll: ctx.accounts.bankroll.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount: received,
+        shares,
+    });
 
     msg!(
-        "Funded escrow with {} tokens. New balance: {}",
-        amount,
-        ctx.accounts.escrow.amount + amount
+        "Funded bankroll with {} tokens for {} shares. New liquidity: {}",
+        received,
+        shares,
+        ctx.accounts.bankroll.liquidity(ctx.accounts.vault.amount) + received
     );
 
     Ok(())
 }
+
+#[derive(Accounts)]
+pub struct SeedShares<'info> {
+    /// Bankroll authority (receives the seed shares)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        has_one = authority @ CasinoError::Unauthorized,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Shared vault token account
+    #[account(
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Liquidity share mint
+    #[account(
+        mut,
+        address = bankroll.share_mint,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Authority's share token account (receives minted shares)
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = share_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub authority_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn seed_handler(ctx: Context<SeedShares>) -> Result<()> {
+    // Only liquidity nobody holds shares in (house profit left after every
+    // LP exited) can be seeded, one share per token
+    let liquidity = ctx.accounts.bankroll.liquidity(ctx.accounts.vault.amount);
+    require!(
+        ctx.accounts.share_mint.supply == 0 && liquidity > 0,
+        CasinoError::SharesAlreadySeeded
+    );
+
+    mint_shares(
+        &ctx.accounts.bankroll,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.authority_share_account,
+        &ctx.accounts.token_program,
+        liquidity,
+    )?;
+
+    emit!(SharesSeeded {
+        bankroll: ctx.accounts.bankroll.key(),
+        authority: ctx.accounts.authority.key(),
+        shares: liquidity,
+    });
+
+    Ok(())
+}
+
+/// Shares due for `amount` newly added to the vault, priced against its
+/// liquidity before the addition
+pub fn deposit_shares(
+    bankroll: &Bankroll,
+    vault: &InterfaceAccount<TokenAccount>,
+    share_mint: &InterfaceAccount<Mint>,
+    amount: u64,
+) -> Result<u64> {
+    let shares = Bankroll::shares_for_deposit(
+        amount,
+        bankroll.liquidity(vault.amount),
+        share_mint.supply,
+    )
+    .ok_or(if share_mint.supply == 0 {
+        CasinoError::SharesNotSeeded
+    } else {
+        CasinoError::InsufficientEscrow
+    })?;
+    require!(shares > 0, CasinoError::DepositTooSmall);
+    Ok(shares)
+}
+
+/// Mint `shares` to `to`, signed by the bankroll PDA
+pub fn mint_shares<'info>(
+    bankroll: &Account<'info, Bankroll>,
+    share_mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    shares: u64,
+) -> Result<()> {
+    let seeds = &[b"bankroll".as_ref(), bankroll.mint.as_ref(), &[bankroll.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = MintTo {
+        mint: share_mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: bankroll.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::mint_to(cpi_ctx, shares)
+}
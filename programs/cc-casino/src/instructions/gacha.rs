@@ -1,16 +1,39 @@
 //! Gacha game instructions
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::state::*;
+use crate::transfer::{required, GameFunds};
+use crate::instructions::balance::{credit_balance, debit_balance};
+use crate::instructions::profile::record_wager;
+use crate::instructions::referral::accrue_referral;
+use crate::instructions::responsible::check_gaming_limits;
+use crate::instructions::session::authorize_bet;
+use crate::instructions::throttle::throttle_bet;
 use crate::{BetPlaced, GachaPull as GachaPullEvent, CasinoError};
 
 #[derive(Accounts)]
 pub struct PullGacha<'info> {
-    /// Player
+    /// Wallet or session key signing the bet (native SOL stakes come from it)
     #[account(mut)]
-    pub player: Signer<'info>,
+    pub signer: Signer<'info>,
+
+    /// Player wallet (owns the bet; payouts go to it)
+    /// CHECK: Must be `signer` unless `session` authorizes it, see `authorize_bet`
+    pub player: UncheckedAccount<'info>,
+
+    /// Session authorizing `signer` to bet for `player`
+    #[account(
+        mut,
+        seeds = [b"session", player.key().as_ref(), signer.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, Session>>,
+
+    /// Relayer paying rent and platform fees (the signer pays when omitted)
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
 
     /// Game state
     #[account(
@@ -20,130 +43,393 @@ pub struct PullGacha<'info> {
     )]
     pub game_state: Account<'info, GameState>,
 
+    /// Global config (bets are refused during an emergency stop)
+    #[account(
+        seeds = [b"global"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ CasinoError::EmergencyStop,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     /// Pull result PDA
     #[account(
         init,
-        payer = player,
+        payer = payer.as_ref().unwrap_or(&signer),
         space = GachaPullResult::LEN,
         seeds = [b"gacha", game_state.key().as_ref(), player.key().as_ref(), &Clock::get()?.unix_timestamp.to_le_bytes()],
         bump
     )]
     pub pull_result: Account<'info, GachaPullResult>,
 
-    /// Player's token account
+    /// Player's token account (token games)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
+        associated_token::mint = mint,
         associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint being bet (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+        constraint = game_state.accepts_mint(&bankroll.mint) @ CasinoError::MintNotAccepted,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint being bet, SPL Token or Token-2022 (token games)
+    #[account(
+        constraint = bankroll.as_ref().is_some_and(|b| b.mint == mint.key()) @ CasinoError::WrongBankroll,
+    )]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
     )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub sol_vault: Option<SystemAccount<'info>>,
 
-    /// Escrow
+    /// Limits and accounting for a non-primary mint (required when betting in one)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        constraint = mint_config.game == game_state.key()
+            && bankroll.as_ref().is_some_and(|b| b.mint == mint_config.mint)
+            @ CasinoError::MintNotAccepted,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Player's internal balance to bet from instead of the token account
+    #[account(
+        mut,
+        constraint = player_balance.player == player.key()
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// Player profile PDA (stats, referrer and rakeback)
+    #[account(
+        init_if_needed,
+        payer = payer.as_ref().unwrap_or(&signer),
+        space = PlayerProfile::LEN,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Per-game bet throttle PDA (cooldown and bets per window)
+    #[account(
+        init_if_needed,
+        payer = payer.as_ref().unwrap_or(&signer),
+        space = PlayerThrottle::LEN,
+        seeds = [b"throttle", game_state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_throttle: Box<Account<'info, PlayerThrottle>>,
+
+    /// VIP tier configuration (no rakeback when absent)
+    #[account(
+        constraint = bankroll.as_ref().is_some_and(|b| vip_config.bankroll == b.key()) @ CasinoError::WrongBankroll,
+    )]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    /// Referrer earnings PDA (required when the player has a referrer)
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
 
     /// System program
     pub system_program: Program<'info, System>,
 
-    /// Token program
-    pub token_program: Program<'info, Token>,
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
-pub fn pull_handler(ctx: Context<PullGacha>, pulls: u8) -> Result<()> {
+pub fn pull_gacha_handler(ctx: Context<PullGacha>, pulls: u8) -> Result<()> {
+    let funds = GameFunds::new(
+        &ctx.accounts.game_state,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.bankroll.as_ref(),
+        ctx.accounts.vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        &ctx.accounts.system_program,
+    );
+
     let game = &mut ctx.accounts.game_state;
     let pull_result = &mut ctx.accounts.pull_result;
     let clock = Clock::get()?;
 
     // Validate pull count
-    require!(pulls >= 1 && pulls <= 10, CasinoError::InvalidPullCount);
+    require!((1..=10).contains(&pulls), CasinoError::InvalidPullCount);
+
+    // Pulls outside the primary mint use the limits and ledger of their MintConfig
+    let bet_mint = funds.mint_key();
+    require!(
+        bet_mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
 
     // Calculate total cost
-    let cost_per_pull = game.config.min_bet;
+    let (cost_per_pull, max_bet) = game.bet_limits(ctx.accounts.mint_config.as_deref());
     let total_cost = cost_per_pull.checked_mul(pulls as u64).unwrap();
-    require!(total_cost <= game.config.max_bet, CasinoError::BetTooLarge);
-
-    // Transfer tokens
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.player_token_account.to_account_info(),
-        to: ctx.accounts.escrow.to_account_info(),
-        authority: ctx.accounts.player.to_account_info(),
+    require!(total_cost <= max_bet, CasinoError::BetTooLarge);
+
+    // Every pull could land legendary, so the allocation must cover that
+    let max_payout =
+        funds.gross_up((total_cost * PrizeTier::Legendary.multiplier_bps() as u64) / 10000)?;
+    require!(
+        max_payout <= game.liquidity_for(ctx.accounts.mint_config.as_deref()),
+        CasinoError::AllocationExceeded
+    );
+
+    // Session keys bet against the cap the player authorized, from the
+    // player's internal balance when staking tokens
+    let from_balance = ctx.accounts.player_balance.is_some();
+    authorize_bet(
+        ctx.accounts.session.as_deref_mut(),
+        ctx.accounts.player.key(),
+        ctx.accounts.signer.key(),
+        game.key(),
+        bet_mint,
+        total_cost,
+        from_balance,
+    )?;
+
+    // Transfer tokens; the stake is what arrives after transfer fees.
+    // Pulls paid from an internal balance are already in the vault.
+    let stake = match ctx.accounts.player_balance.as_deref_mut() {
+        Some(balance) => {
+            debit_balance(required(ctx.accounts.bankroll.as_deref_mut())?, balance, total_cost)?;
+            total_cost
+        }
+        None => funds.collect(
+            &ctx.accounts.signer.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            total_cost,
+        )?,
     };
-    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::transfer(cpi_ctx, total_cost)?;
+
+    // Transfer platform fee (SOL) from the relayer, or the signer without one
+    let payer = ctx.accounts.payer.as_ref().unwrap_or(&ctx.accounts.signer).to_account_info();
+    let fee = game.config.platform_fee_lamports;
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.clone(),
+                to: game.to_account_info(),
+            },
+        ),
+        fee,
+    )?;
 
     // Initialize pull result
     pull_result.player = ctx.accounts.player.key();
+    pull_result.from_balance = from_balance;
+    pull_result.rent_payer = payer.key();
     pull_result.game = game.key();
+    pull_result.mint = bet_mint;
     pull_result.pull_count = pulls;
     pull_result.tiers = [0u8; 10];
+    pull_result.total_cost = stake;
+    pull_result.fee_amount = fee;
     pull_result.total_payout = 0;
     pull_result.vrf_result = [0u8; 32];
     pull_result.resolved = false;
     pull_result.pulled_at = clock.unix_timestamp;
+    pull_result.pulled_slot = clock.slot;
+    pull_result.resolved_at = 0;
+    pull_result.cost_per_pull = cost_per_pull;
+    pull_result.version = GachaPullResult::VERSION;
     pull_result.bump = ctx.bumps.pull_result;
 
     // Update game stats
-    game.total_volume = game.total_volume.checked_add(total_cost).unwrap();
+    game.add_volume(ctx.accounts.mint_config.as_deref_mut(), stake);
+    game.total_fees = game.total_fees.checked_add(fee).unwrap();
 
     emit!(BetPlaced {
         game: game.key(),
         player: ctx.accounts.player.key(),
-        bet_amount: total_cost,
-        fee_amount: game.config.platform_fee_lamports,
+        bet_amount: stake,
+        fee_amount: fee,
     });
 
+    throttle_bet(
+        &ctx.accounts.game_state,
+        &mut ctx.accounts.player_throttle,
+        ctx.accounts.player.key(),
+        ctx.bumps.player_throttle,
+        clock.unix_timestamp,
+    )?;
+
+    let profile = &mut ctx.accounts.player_profile;
+    profile.ensure_initialized(
+        ctx.accounts.player.key(),
+        ctx.bumps.player_profile,
+        clock.unix_timestamp,
+    );
+    check_gaming_limits(
+        profile,
+        stake,
+        ctx.accounts.game_state.tracks_rewards(&bet_mint),
+        clock.unix_timestamp,
+    )?;
+
+    // Volume, rakeback and referral rewards are denominated in the primary bankroll token
+    if ctx.accounts.game_state.tracks_rewards(&bet_mint) {
+        record_wager(
+            &mut ctx.accounts.game_state,
+            required(ctx.accounts.bankroll.as_mut())?,
+            profile,
+            ctx.accounts.vip_config.as_ref(),
+            stake,
+            clock.unix_timestamp,
+        )?;
+        accrue_referral(
+            &mut ctx.accounts.game_state,
+            required(ctx.accounts.bankroll.as_mut())?,
+            &ctx.accounts.player_profile,
+            ctx.accounts.referrer_account.as_mut(),
+            stake,
+        )?;
+    }
+
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct ResolveGacha<'info> {
-    /// VRF authority
+    /// VRF authority (game resolver)
     pub vrf_authority: Signer<'info>,
 
     /// Game state
+    #[account(
+        mut,
+        constraint = game_state.resolver == vrf_authority.key() @ CasinoError::Unauthorized,
+    )]
     pub game_state: Account<'info, GameState>,
 
     /// Pull result to resolve
     #[account(
         mut,
+        has_one = player @ CasinoError::Unauthorized,
+        constraint = pull_result.game == game_state.key() @ CasinoError::Unauthorized,
         constraint = !pull_result.resolved @ CasinoError::AlreadyResolved,
     )]
     pub pull_result: Account<'info, GachaPullResult>,
 
     /// Player
-    /// CHECK: Only for key matching
+    /// CHECK: Matched against the pull's player
     pub player: AccountInfo<'info>,
 
-    /// Player's token account
+    /// Player profile (stats)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
+        seeds = [b"profile", player.key().as_ref()],
+        bump = player_profile.bump,
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Player's token account (token games)
+    #[account(
+        mut,
+        associated_token::mint = pull_result.mint,
         associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint the bet was placed in (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", pull_result.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint the bet was placed in, SPL Token or Token-2022 (token games)
+    #[account(address = pull_result.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = pull_result.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Limits and accounting for a non-primary mint (required when the bet used one)
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), pull_result.mint.as_ref()],
+        bump = mint_config.bump,
     )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub mint_config: Option<Account<'info, MintConfig>>,
 
-    /// Escrow
+    /// Player's internal balance (required when the stake came from it)
     #[account(
         mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        constraint = player_balance.player == pull_result.player
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
 
-    /// Token program
-    pub token_program: Program<'info, Token>,
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
-pub fn resolve_handler(ctx: Context<ResolveGacha>, vrf_result: [u8; 32]) -> Result<()> {
+pub fn resolve_gacha_handler(ctx: Context<ResolveGacha>, vrf_result: [u8; 32]) -> Result<()> {
+    let funds = GameFunds::new(
+        &ctx.accounts.game_state,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.bankroll.as_ref(),
+        ctx.accounts.vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        &ctx.accounts.system_program,
+    );
+
     let game = &ctx.accounts.game_state;
     let pull_result = &mut ctx.accounts.pull_result;
 
-    // Determine prizes for each pull
-    let cost_per_pull = game.config.min_bet;
+    require!(
+        pull_result.mint == game.cc_mint || ctx.accounts.mint_config.is_some(),
+        CasinoError::MintNotAccepted
+    );
+    require!(
+        !game.cancel_window_open(pull_result.pulled_slot, Clock::get()?.slot),
+        CasinoError::CancelWindowOpen
+    );
+
+    // Determine prizes for each pull, priced as when the pulls were made
+    let cost_per_pull = pull_result.cost_per_pull;
     let mut total_payout = 0u64;
     let mut has_rare_or_better = false;
 
@@ -174,25 +460,37 @@ pub fn resolve_handler(ctx: Context<ResolveGacha>, vrf_result: [u8; 32]) -> Resu
     pull_result.vrf_result = vrf_result;
     pull_result.total_payout = total_payout;
     pull_result.resolved = true;
+    pull_result.resolved_at = Clock::get()?.unix_timestamp;
+
+    // Transfer payout if any (the vault covers outbound transfer fees);
+    // balance payouts never leave the vault
+    let gross_payout = if pull_result.from_balance {
+        total_payout
+    } else {
+        funds.gross_up(total_payout)?
+    };
+    if total_payout > 0 && pull_result.from_balance {
+        require!(funds.balance()? >= gross_payout, CasinoError::InsufficientEscrow);
+        credit_balance(
+            required(ctx.accounts.bankroll.as_deref_mut())?,
+            required(ctx.accounts.player_balance.as_deref_mut())?,
+            total_payout,
+        )?;
+    } else if total_payout > 0 {
+        funds.pay(
+            &ctx.accounts.player,
+            ctx.accounts.player_token_account.as_ref(),
+            gross_payout,
+        )?;
+    }
 
-    // Transfer payout if any
-    if total_payout > 0 {
-        let slug = game.slug_as_str();
-        let seeds = &[b"game".as_ref(), slug.as_bytes(), &[game.escrow_bump]];
-        let signer_seeds = &[&seeds[..]];
-
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.escrow.to_account_info(),
-            to: ctx.accounts.player_token_account.to_account_info(),
-            authority: ctx.accounts.game_state.to_account_info(),
-        };
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            signer_seeds,
-        );
-        token::transfer(cpi_ctx, total_payout)?;
+    let total_cost = pull_result.total_cost;
+    let game = &mut ctx.accounts.game_state;
+    game.settle(ctx.accounts.mint_config.as_deref_mut(), total_cost, gross_payout);
+    if game.tracks_rewards(&pull_result.mint) {
+        ctx.accounts.player_profile.record_result(total_cost, total_payout);
     }
+    let game = &ctx.accounts.game_state;
 
     // Emit events for each pull
     for i in 0..pull_result.pull_count as usize {
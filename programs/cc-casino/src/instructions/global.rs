@@ -0,0 +1,167 @@
+//! Program-wide config and emergency stop instructions
+
+use anchor_lang::prelude::*;
+
+use crate::program::CcCasino;
+use crate::state::*;
+use crate::{CasinoError, CreatorAdded, CreatorRemoved, EmergencyStopSet};
+
+#[derive(Accounts)]
+pub struct InitializeGlobal<'info> {
+    /// Program upgrade authority (becomes the admin)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global config PDA
+    #[account(
+        init,
+        payer = admin,
+        space = GlobalConfig::LEN,
+        seeds = [b"global"],
+        bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Game registry PDA
+    #[account(
+        init,
+        payer = admin,
+        space = GameRegistry::LEN,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, GameRegistry>,
+
+    /// This program
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, CcCasino>,
+
+    /// Program data (proves the upgrade authority)
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ CasinoError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_global_handler(ctx: Context<InitializeGlobal>) -> Result<()> {
+    let global = &mut ctx.accounts.global_config;
+    global.admin = ctx.accounts.admin.key();
+    global.paused = false;
+    global.version = GlobalConfig::VERSION;
+    global.bump = ctx.bumps.global_config;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.game_count = 0;
+    registry.version = GameRegistry::VERSION;
+    registry.bump = ctx.bumps.registry;
+
+    msg!("Global config initialized, admin {}", global.admin);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetEmergencyStop<'info> {
+    /// Global admin
+    pub admin: Signer<'info>,
+
+    /// Global config
+    #[account(
+        mut,
+        has_one = admin @ CasinoError::Unauthorized,
+        seeds = [b"global"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+pub fn set_emergency_stop_handler(ctx: Context<SetEmergencyStop>, paused: bool) -> Result<()> {
+    let global = &mut ctx.accounts.global_config;
+    global.paused = paused;
+
+    emit!(EmergencyStopSet {
+        admin: global.admin,
+        paused,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AddGameCreator<'info> {
+    /// Global admin (pays for the allowlist entry)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global config
+    #[account(
+        has_one = admin @ CasinoError::Unauthorized,
+        seeds = [b"global"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Allowlist entry PDA
+    #[account(
+        init,
+        payer = admin,
+        space = GameCreator::LEN,
+        seeds = [b"creator", creator.as_ref()],
+        bump
+    )]
+    pub game_creator: Account<'info, GameCreator>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_creator_handler(ctx: Context<AddGameCreator>, creator: Pubkey) -> Result<()> {
+    let entry = &mut ctx.accounts.game_creator;
+    entry.creator = creator;
+    entry.added_by = ctx.accounts.admin.key();
+    entry.added_at = Clock::get()?.unix_timestamp;
+    entry.version = GameCreator::VERSION;
+    entry.bump = ctx.bumps.game_creator;
+
+    emit!(CreatorAdded {
+        creator,
+        admin: entry.added_by,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveGameCreator<'info> {
+    /// Global admin (receives the entry rent)
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global config
+    #[account(
+        has_one = admin @ CasinoError::Unauthorized,
+        seeds = [b"global"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Allowlist entry to remove
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"creator", game_creator.creator.as_ref()],
+        bump = game_creator.bump,
+    )]
+    pub game_creator: Account<'info, GameCreator>,
+}
+
+pub fn remove_creator_handler(ctx: Context<RemoveGameCreator>) -> Result<()> {
+    emit!(CreatorRemoved {
+        creator: ctx.accounts.game_creator.creator,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
@@ -1,10 +1,10 @@
 //! Initialize game instruction
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint};
-use anchor_spl::associated_token::AssociatedToken;
 
+use crate::instructions::config::validate_config;
 use crate::state::*;
+use crate::transfer::{required, transfer_to_sol_vault};
 use crate::{GameInitialized, CasinoError};
 
 #[derive(Accounts)]
@@ -14,6 +14,38 @@ pub struct InitializeGame<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Global config (its admin may always create games)
+    #[account(
+        seeds = [b"global"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Creator allowlist entry (required unless the authority is the admin)
+    #[account(
+        seeds = [b"creator", authority.key().as_ref()],
+        bump = game_creator.bump,
+    )]
+    pub game_creator: Option<Account<'info, GameCreator>>,
+
+    /// Game registry
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, GameRegistry>>,
+
+    /// Registry entry PDA for this game
+    #[account(
+        init,
+        payer = authority,
+        space = RegistryEntry::LEN,
+        seeds = [b"registry_entry".as_ref(), &registry.game_count.to_le_bytes()],
+        bump
+    )]
+    pub registry_entry: Box<Account<'info, RegistryEntry>>,
+
     /// Game state PDA
     #[account(
         init,
@@ -24,35 +56,42 @@ pub struct InitializeGame<'info> {
     )]
     pub game_state: Account<'info, GameState>,
 
-    /// Escrow token account (holds $CC for payouts)
+    /// Shared bankroll the game draws liquidity from (token games)
     #[account(
-        init,
-        payer = authority,
-        associated_token::mint = cc_mint,
-        associated_token::authority = game_state,
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub bankroll: Option<Account<'info, Bankroll>>,
 
-    /// $CC token mint
-    pub cc_mint: Account<'info, Mint>,
+    /// Lamport vault PDA (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
 
     /// System program
     pub system_program: Program<'info, System>,
-
-    /// Token program
-    pub token_program: Program<'info, Token>,
-
-    /// Associated token program
-    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn handler(
+pub fn initialize_game_handler(
     ctx: Context<InitializeGame>,
     game_type: GameType,
     slug: String,
     config: GameConfig,
+    currency: Currency,
+    config_delay: i64,
 ) -> Result<()> {
-    require!(slug.len() <= 32, CasinoError::BetTooSmall); // Reusing error for now
+    require!(slug.len() <= 32, CasinoError::SlugTooLong);
+    validate_config(game_type, &config)?;
+    require!(config_delay >= 0, CasinoError::InvalidConfigDelay);
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.global_config.admin
+            || ctx.accounts.game_creator.is_some(),
+        CasinoError::CreatorNotAllowed
+    );
 
     let game = &mut ctx.accounts.game_state;
     let clock = Clock::get()?;
@@ -66,19 +105,72 @@ pub fn handler(
     game.game_type = game_type;
     game.slug = slug_bytes;
     game.config = config;
-    game.cc_mint = ctx.accounts.cc_mint.key();
+    game.version = GameState::VERSION;
     game.escrow_bump = ctx.bumps.game_state;
     game.is_active = true;
     game.total_volume = 0;
     game.total_fees = 0;
     game.current_round = 0;
     game.created_at = clock.unix_timestamp;
-
+    game.burn_bps = 0;
+    game.total_burned = 0;
+    game.realized_profit = 0;
+    game.burned_profit_mark = 0;
+    game.allocation = 0;
+    game.currency = currency;
+    game.accepted_mint_count = 0;
+    game.config_delay = config_delay;
+    game.pending_authority = Pubkey::default();
+    game.operator = game.authority;
+    game.resolver = game.authority;
+    game.treasurer = game.authority;
+    game.resolution_timeout_slots = DEFAULT_RESOLUTION_TIMEOUT_SLOTS;
+
+    match currency {
+        Currency::Token => {
+            let bankroll = required(ctx.accounts.bankroll.as_mut())?;
+            bankroll.game_count = bankroll.game_count.checked_add(1).unwrap();
+            ctx.accounts.game_state.cc_mint = bankroll.mint;
+            ctx.accounts.game_state.sol_vault_bump = 0;
+        }
+        Currency::Native => {
+            // Keep the vault rent-exempt so payouts can never leave it under the minimum
+            let sol_vault = required(ctx.accounts.sol_vault.as_ref())?;
+            let rent_exempt = Rent::get()?.minimum_balance(0);
+            let top_up = rent_exempt.saturating_sub(sol_vault.lamports());
+            if top_up > 0 {
+                transfer_to_sol_vault(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.authority.to_account_info(),
+                    sol_vault,
+                    top_up,
+                )?;
+            }
+            ctx.accounts.game_state.cc_mint = Pubkey::default();
+            ctx.accounts.game_state.sol_vault_bump = ctx.bumps.sol_vault;
+        }
+    }
+
+    let registry = &mut ctx.accounts.registry;
+    let entry = &mut ctx.accounts.registry_entry;
+    entry.index = registry.game_count;
+    entry.game = ctx.accounts.game_state.key();
+    entry.slug = slug_bytes;
+    entry.game_type = game_type;
+    entry.creator = ctx.accounts.authority.key();
+    entry.created_at = clock.unix_timestamp;
+    entry.version = RegistryEntry::VERSION;
+    entry.bump = ctx.bumps.registry_entry;
+    registry.game_count = registry.game_count.checked_add(1).unwrap();
+
+    let game = &ctx.accounts.game_state;
     emit!(GameInitialized {
         game: game.key(),
         game_type,
         slug: slug.clone(),
         authority: ctx.accounts.authority.key(),
+        currency,
+        registry_index: ctx.accounts.registry_entry.index,
     });
 
     msg!("Game initialized: {} ({})", slug, game.key());
@@ -1,24 +1,56 @@
 //! Jackpot game instructions
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::state::{*, calculate_jackpot_winner};
+use crate::transfer::{required, GameFunds};
+use crate::instructions::balance::debit_balance;
+use crate::instructions::profile::record_wager;
+use crate::instructions::referral::accrue_referral;
+use crate::instructions::responsible::check_gaming_limits;
+use crate::instructions::session::authorize_bet;
+use crate::instructions::throttle::throttle_bet;
 use crate::{BetPlaced, JackpotWon, CasinoError};
 
 #[derive(Accounts)]
 pub struct EnterJackpot<'info> {
-    /// Player
+    /// Wallet or session key signing the bet (native SOL stakes come from it)
     #[account(mut)]
-    pub player: Signer<'info>,
+    pub signer: Signer<'info>,
+
+    /// Player wallet (owns the bet; payouts go to it)
+    /// CHECK: Must be `signer` unless `session` authorizes it, see `authorize_bet`
+    pub player: UncheckedAccount<'info>,
+
+    /// Session authorizing `signer` to bet for `player`
+    #[account(
+        mut,
+        seeds = [b"session", player.key().as_ref(), signer.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, Session>>,
+
+    /// Relayer paying rent and platform fees (the signer pays when omitted)
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
 
     /// Game state
     #[account(
+        mut,
         constraint = game_state.is_active @ CasinoError::GameNotActive,
         constraint = game_state.game_type == GameType::Jackpot @ CasinoError::GameNotActive,
     )]
     pub game_state: Account<'info, GameState>,
 
+    /// Global config (bets are refused during an emergency stop)
+    #[account(
+        seeds = [b"global"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ CasinoError::EmergencyStop,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     /// Current round
     #[account(
         mut,
@@ -31,87 +63,242 @@ pub struct EnterJackpot<'info> {
     /// Participant PDA
     #[account(
         init_if_needed,
-        payer = player,
+        payer = payer.as_ref().unwrap_or(&signer),
         space = RoundParticipant::LEN,
         seeds = [b"participant", round_state.key().as_ref(), player.key().as_ref()],
         bump
     )]
     pub participant: Account<'info, RoundParticipant>,
 
-    /// Player's token account
+    /// Player's token account (token games)
     #[account(
         mut,
         associated_token::mint = game_state.cc_mint,
         associated_token::authority = player,
+        associated_token::token_program = token_program,
     )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Escrow
+    /// Shared bankroll (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", game_state.cc_mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Bankroll token mint, SPL Token or Token-2022 (token games)
+    #[account(address = game_state.cc_mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
     #[account(
         mut,
         associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Player's internal balance to bet from instead of the token account
+    #[account(
+        mut,
+        constraint = player_balance.player == player.key()
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// Player profile PDA (stats, referrer and rakeback)
+    #[account(
+        init_if_needed,
+        payer = payer.as_ref().unwrap_or(&signer),
+        space = PlayerProfile::LEN,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Per-game bet throttle PDA (cooldown and bets per window)
+    #[account(
+        init_if_needed,
+        payer = payer.as_ref().unwrap_or(&signer),
+        space = PlayerThrottle::LEN,
+        seeds = [b"throttle", game_state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_throttle: Box<Account<'info, PlayerThrottle>>,
+
+    /// VIP tier configuration (no rakeback when absent)
+    #[account(
+        constraint = bankroll.as_ref().is_some_and(|b| vip_config.bankroll == b.key()) @ CasinoError::WrongBankroll,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    /// Referrer earnings PDA (required when the player has a referrer)
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
 
     /// System program
     pub system_program: Program<'info, System>,
 
-    /// Token program
-    pub token_program: Program<'info, Token>,
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 pub fn enter_handler(ctx: Context<EnterJackpot>, ticket_amount: u64) -> Result<()> {
+    let funds = GameFunds::new(
+        &ctx.accounts.game_state,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.bankroll.as_ref(),
+        ctx.accounts.vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        &ctx.accounts.system_program,
+    );
+
     let game = &ctx.accounts.game_state;
     let round = &mut ctx.accounts.round_state;
     let participant = &mut ctx.accounts.participant;
     let clock = Clock::get()?;
 
     // Validate bet (ticket_amount is number of tickets, each ticket = min_bet)
+    require!(ticket_amount > 0, CasinoError::NoTickets);
     let bet_amount = ticket_amount.checked_mul(game.config.min_bet).unwrap();
     require!(bet_amount <= game.config.max_bet, CasinoError::BetTooLarge);
 
-    // Transfer tokens
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.player_token_account.to_account_info(),
-        to: ctx.accounts.escrow.to_account_info(),
-        authority: ctx.accounts.player.to_account_info(),
+    // Session keys bet against the cap the player authorized
+    authorize_bet(
+        ctx.accounts.session.as_deref_mut(),
+        ctx.accounts.player.key(),
+        ctx.accounts.signer.key(),
+        game.key(),
+        game.cc_mint,
+        bet_amount,
+        ctx.accounts.player_balance.is_some(),
+    )?;
+
+    // Transfer tokens; the stake is what arrives after transfer fees.
+    // Bets from an internal balance are already in the vault.
+    let stake = match ctx.accounts.player_balance.as_deref_mut() {
+        Some(balance) => {
+            debit_balance(required(ctx.accounts.bankroll.as_deref_mut())?, balance, bet_amount)?;
+            bet_amount
+        }
+        None => funds.collect(
+            &ctx.accounts.signer.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            bet_amount,
+        )?,
     };
-    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::transfer(cpi_ctx, bet_amount)?;
+
+    // Transfer platform fee (SOL) from the relayer, or the signer without one
+    let payer = ctx.accounts.payer.as_ref().unwrap_or(&ctx.accounts.signer).to_account_info();
+    let fee = game.config.platform_fee_lamports;
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.clone(),
+                to: game.to_account_info(),
+            },
+        ),
+        fee,
+    )?;
 
     // Update or initialize participant
     if participant.joined_at == 0 {
         participant.player = ctx.accounts.player.key();
         participant.round = round.key();
-        participant.bet_amount = bet_amount;
+        participant.mint = game.cc_mint;
+        participant.bet_amount = stake;
         participant.joined_at = clock.unix_timestamp;
+        participant.rent_payer = payer.key();
+        participant.version = RoundParticipant::VERSION;
         participant.bump = ctx.bumps.participant;
         round.participant_count += 1;
     } else {
-        participant.bet_amount = participant.bet_amount.checked_add(bet_amount).unwrap();
+        participant.bet_amount = participant.bet_amount.checked_add(stake).unwrap();
     }
+    // Tokens from either source end up in the vault, so once any entry came
+    // from the balance the whole stake is refunded there
+    participant.from_balance |= ctx.accounts.player_balance.is_some();
+    participant.fee_amount = participant.fee_amount.checked_add(fee).unwrap();
 
     // Update round pool
-    round.pool_size = round.pool_size.checked_add(bet_amount).unwrap();
+    round.pool_size = round.pool_size.checked_add(stake).unwrap();
+
+    let game = &mut ctx.accounts.game_state;
+    game.total_fees = game.total_fees.checked_add(fee).unwrap();
 
     emit!(BetPlaced {
         game: game.key(),
         player: ctx.accounts.player.key(),
-        bet_amount,
-        fee_amount: game.config.platform_fee_lamports,
+        bet_amount: stake,
+        fee_amount: fee,
     });
 
+    throttle_bet(
+        &ctx.accounts.game_state,
+        &mut ctx.accounts.player_throttle,
+        ctx.accounts.player.key(),
+        ctx.bumps.player_throttle,
+        clock.unix_timestamp,
+    )?;
+
+    let profile = &mut ctx.accounts.player_profile;
+    profile.ensure_initialized(
+        ctx.accounts.player.key(),
+        ctx.bumps.player_profile,
+        clock.unix_timestamp,
+    );
+    check_gaming_limits(
+        profile,
+        stake,
+        ctx.accounts.game_state.tracks_rewards(&ctx.accounts.game_state.cc_mint),
+        clock.unix_timestamp,
+    )?;
+
+    // Volume, rakeback and referral rewards are denominated in the primary bankroll token
+    if ctx.accounts.game_state.tracks_rewards(&ctx.accounts.game_state.cc_mint) {
+        record_wager(
+            &mut ctx.accounts.game_state,
+            required(ctx.accounts.bankroll.as_mut())?,
+            profile,
+            ctx.accounts.vip_config.as_ref(),
+            stake,
+            clock.unix_timestamp,
+        )?;
+        accrue_referral(
+            &mut ctx.accounts.game_state,
+            required(ctx.accounts.bankroll.as_mut())?,
+            &ctx.accounts.player_profile,
+            ctx.accounts.referrer_account.as_mut(),
+            stake,
+        )?;
+    }
+
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct DrawJackpot<'info> {
-    /// VRF authority
+    /// VRF authority (game resolver)
     pub vrf_authority: Signer<'info>,
 
     /// Game state
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = game_state.resolver == vrf_authority.key() @ CasinoError::Unauthorized,
+    )]
     pub game_state: Account<'info, GameState>,
 
     /// Round to draw
@@ -127,64 +314,102 @@ pub struct DrawJackpot<'info> {
     /// CHECK: We'll find the winner from VRF
     pub winner: AccountInfo<'info>,
 
-    /// Winner's token account
+    /// Winner profile (stats)
+    #[account(
+        mut,
+        seeds = [b"profile", winner.key().as_ref()],
+        bump = winner_profile.bump,
+    )]
+    pub winner_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// Winner's token account (token games)
     #[account(
         mut,
         associated_token::mint = game_state.cc_mint,
         associated_token::authority = winner,
+        associated_token::token_program = token_program,
     )]
-    pub winner_token_account: Account<'info, TokenAccount>,
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Escrow
+    /// Shared bankroll (token games)
+    #[account(
+        seeds = [b"bankroll", game_state.cc_mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Bankroll token mint, SPL Token or Token-2022 (token games)
+    #[account(address = game_state.cc_mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
     #[account(
         mut,
         associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
 
-    /// Token program
-    pub token_program: Program<'info, Token>,
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 pub fn draw_handler(ctx: Context<DrawJackpot>, vrf_result: [u8; 32]) -> Result<()> {
+    let funds = GameFunds::new(
+        &ctx.accounts.game_state,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.bankroll.as_ref(),
+        ctx.accounts.vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        &ctx.accounts.system_program,
+    );
+
     let game = &ctx.accounts.game_state;
     let round = &mut ctx.accounts.round_state;
     let clock = Clock::get()?;
 
     // Calculate winner index (simplified - in production, iterate through all participants)
     let total_tickets = round.pool_size / game.config.min_bet;
-    let _winner_index = calculate_jackpot_winner(&vrf_result, total_tickets as u32);
+    require!(total_tickets > 0, CasinoError::NoTickets);
+    let _winner_index = calculate_jackpot_winner(&vrf_result, total_tickets);
 
     // Calculate payout (5% house cut)
     let house_cut = (round.pool_size * game.config.house_edge_bps as u64) / 10000;
     let payout = round.pool_size - house_cut;
+    let gross_payout = funds.gross_up(payout)?;
 
-    // Transfer payout
-    let slug = game.slug_as_str();
-    let seeds = &[b"game".as_ref(), slug.as_bytes(), &[game.escrow_bump]];
-    let signer_seeds = &[&seeds[..]];
-
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.escrow.to_account_info(),
-        to: ctx.accounts.winner_token_account.to_account_info(),
-        authority: ctx.accounts.game_state.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        cpi_accounts,
-        signer_seeds,
-    );
-    token::transfer(cpi_ctx, payout)?;
+    // Transfer payout (the vault covers outbound transfer fees)
+    funds.pay(
+        &ctx.accounts.winner,
+        ctx.accounts.winner_token_account.as_ref(),
+        gross_payout,
+    )?;
 
     // Update round
     round.vrf_result = vrf_result;
     round.phase = RoundPhase::Ended;
     round.ended_at = clock.unix_timestamp;
     round.result[..32].copy_from_slice(ctx.accounts.winner.key.as_ref());
+    ctx.accounts.game_state.record_settlement(round.pool_size, gross_payout);
+    if ctx.accounts.game_state.tracks_rewards(&ctx.accounts.game_state.cc_mint) {
+        ctx.accounts.winner_profile.record_result(0, payout);
+    }
 
     emit!(JackpotWon {
-        game: game.key(),
+        game: ctx.accounts.game_state.key(),
         winner: ctx.accounts.winner.key(),
         pool_size: round.pool_size,
         payout,
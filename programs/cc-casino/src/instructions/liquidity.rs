@@ -0,0 +1,236 @@
+//! Liquidity withdrawal queue instructions
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::state::*;
+use crate::transfer::transfer_from_vault;
+use crate::{CasinoError, WithdrawalExecuted, WithdrawalRequested};
+
+#[derive(Accounts)]
+pub struct RequestWithdrawal<'info> {
+    /// Liquidity provider
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Withdrawal request PDA
+    #[account(
+        init,
+        payer = owner,
+        space = WithdrawalRequest::LEN,
+        seeds = [b"withdrawal", bankroll.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+
+    /// Liquidity share mint
+    #[account(address = bankroll.share_mint)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Owner's share token account
+    #[account(
+        mut,
+        associated_token::mint = bankroll.share_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bankroll-held share escrow (locks shares until execution)
+    #[account(
+        mut,
+        associated_token::mint = bankroll.share_mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub share_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn request_handler(ctx: Context<RequestWithdrawal>, shares: u64) -> Result<()> {
+    require!(shares > 0, CasinoError::DepositTooSmall);
+
+    let clock = Clock::get()?;
+
+    // Lock shares; they keep absorbing profit and loss until execution
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.owner_share_account.to_account_info(),
+        mint: ctx.accounts.share_mint.to_account_info(),
+        to: ctx.accounts.share_escrow.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, shares, ctx.accounts.share_mint.decimals)?;
+
+    let bankroll = &mut ctx.accounts.bankroll;
+    bankroll.pending_withdrawal_shares = bankroll
+        .pending_withdrawal_shares
+        .checked_add(shares)
+        .unwrap();
+
+    let request = &mut ctx.accounts.withdrawal_request;
+    request.owner = ctx.accounts.owner.key();
+    request.bankroll = bankroll.key();
+    request.shares = shares;
+    request.requested_at = clock.unix_timestamp;
+    request.available_at = clock
+        .unix_timestamp
+        .checked_add(bankroll.withdrawal_delay)
+        .unwrap();
+    request.version = WithdrawalRequest::VERSION;
+    request.bump = ctx.bumps.withdrawal_request;
+
+    emit!(WithdrawalRequested {
+        bankroll: bankroll.key(),
+        owner: request.owner,
+        shares,
+        available_at: request.available_at,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteWithdrawal<'info> {
+    /// Liquidity provider
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Withdrawal request (closed back to the owner)
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = bankroll,
+        seeds = [b"withdrawal", bankroll.key().as_ref(), owner.key().as_ref()],
+        bump = withdrawal_request.bump,
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+
+    /// Liquidity share mint
+    #[account(
+        mut,
+        address = bankroll.share_mint,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Bankroll token mint
+    #[account(address = bankroll.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Bankroll-held share escrow
+    #[account(
+        mut,
+        associated_token::mint = bankroll.share_mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub share_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared vault
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner's token account (receives redeemed liquidity)
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn execute_withdrawal_handler(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+    let request = &ctx.accounts.withdrawal_request;
+    let clock = Clock::get()?;
+
+    require!(
+        clock.unix_timestamp >= request.available_at,
+        CasinoError::WithdrawalLocked
+    );
+
+    // Redeem at the vault balance as of execution, not as of the request
+    let shares = request.shares;
+    let liquidity = ctx.accounts.bankroll.liquidity(ctx.accounts.vault.amount);
+    let amount = Bankroll::amount_for_shares(shares, liquidity, ctx.accounts.share_mint.supply);
+
+    // Liquidity backing game allocations stays until the authority rebalances
+    require!(
+        amount <= liquidity.saturating_sub(ctx.accounts.bankroll.total_allocated),
+        CasinoError::LiquidityAllocated
+    );
+
+    let bankroll = &ctx.accounts.bankroll;
+    let seeds = &[b"bankroll".as_ref(), bankroll.mint.as_ref(), &[bankroll.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        from: ctx.accounts.share_escrow.to_account_info(),
+        authority: ctx.accounts.bankroll.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::burn(cpi_ctx, shares)?;
+
+    if amount > 0 {
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.bankroll,
+            &ctx.accounts.vault,
+            &ctx.accounts.owner_token_account,
+            amount,
+        )?;
+    }
+
+    let bankroll = &mut ctx.accounts.bankroll;
+    bankroll.pending_withdrawal_shares = bankroll
+        .pending_withdrawal_shares
+        .checked_sub(shares)
+        .unwrap();
+
+    emit!(WithdrawalExecuted {
+        bankroll: bankroll.key(),
+        owner: ctx.accounts.owner.key(),
+        shares,
+        amount,
+    });
+
+    msg!("Redeemed {} shares for {} tokens", shares, amount);
+
+    Ok(())
+}
@@ -0,0 +1,420 @@
+//! In-place account layout migration instructions
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::instructions::fund::{deposit_shares, mint_shares};
+use crate::state::*;
+use crate::transfer::transfer_fee;
+use crate::{AccountMigrated, CasinoError, EscrowMigrated};
+
+#[derive(Accounts)]
+pub struct MigrateGame<'info> {
+    /// Pays rent for the grown account (anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Game state in any supported layout
+    /// CHECK: Owner checked here, discriminator and layout by `load_versioned`
+    #[account(mut, owner = crate::ID)]
+    pub game_state: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn game_handler(ctx: Context<MigrateGame>) -> Result<()> {
+    let info = ctx.accounts.game_state.to_account_info();
+    let (game, from_version) = load_versioned(
+        &info.try_borrow_data()?,
+        GameStateV0::LEN,
+        GameStateV0::upgrade,
+    )?;
+    check_upgrade(from_version, GameState::VERSION)?;
+
+    write_upgraded(&info, &ctx.accounts.payer, &ctx.accounts.system_program, GameState::LEN, &game)?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        from_version,
+        to_version: GameState::VERSION,
+    });
+
+    msg!("Migrated game {} from v{} to v{}", game.slug_as_str(), from_version, GameState::VERSION);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateRound<'info> {
+    /// Pays rent for the grown account (anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Round state in any supported layout
+    /// CHECK: Owner checked here, discriminator and layout by `load_versioned`
+    #[account(mut, owner = crate::ID)]
+    pub round_state: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn round_handler(ctx: Context<MigrateRound>) -> Result<()> {
+    let info = ctx.accounts.round_state.to_account_info();
+    let slot = Clock::get()?.slot;
+    let (round, from_version) = load_versioned(
+        &info.try_borrow_data()?,
+        RoundStateV0::LEN,
+        |legacy: RoundStateV0| legacy.upgrade(slot),
+    )?;
+    check_upgrade(from_version, RoundState::VERSION)?;
+
+    write_upgraded(&info, &ctx.accounts.payer, &ctx.accounts.system_program, RoundState::LEN, &round)?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        from_version,
+        to_version: RoundState::VERSION,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateParticipant<'info> {
+    /// Pays rent for the grown account (anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Round participant in any supported layout
+    /// CHECK: Owner checked here, discriminator and layout by `load_versioned`
+    #[account(mut, owner = crate::ID)]
+    pub participant: UncheckedAccount<'info>,
+
+    /// Round the participant joined (already migrated)
+    pub round_state: Account<'info, RoundState>,
+
+    /// Game the round belongs to (already migrated)
+    #[account(address = round_state.game)]
+    pub game_state: Account<'info, GameState>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn participant_handler(ctx: Context<MigrateParticipant>) -> Result<()> {
+    let info = ctx.accounts.participant.to_account_info();
+
+    // Legacy participants always bet in the game's primary mint
+    let mint = ctx.accounts.game_state.cc_mint;
+    let (participant, from_version) = load_versioned(
+        &info.try_borrow_data()?,
+        RoundParticipantV0::LEN,
+        |legacy: RoundParticipantV0| legacy.upgrade(mint),
+    )?;
+    check_upgrade(from_version, RoundParticipant::VERSION)?;
+    require!(
+        participant.round == ctx.accounts.round_state.key(),
+        CasinoError::NotInRound
+    );
+
+    write_upgraded(
+        &info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        RoundParticipant::LEN,
+        &participant,
+    )?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        from_version,
+        to_version: RoundParticipant::VERSION,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateBet<'info> {
+    /// Pays rent for the grown account (anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Player bet in any supported layout
+    /// CHECK: Owner checked here, discriminator and layout by `load_versioned`
+    #[account(mut, owner = crate::ID)]
+    pub player_bet: UncheckedAccount<'info>,
+
+    /// Game the bet was placed in (already migrated)
+    pub game_state: Account<'info, GameState>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn bet_handler(ctx: Context<MigrateBet>) -> Result<()> {
+    let info = ctx.accounts.player_bet.to_account_info();
+    let game = &ctx.accounts.game_state;
+
+    // Legacy bets were in the primary mint and paid at the current multiplier
+    let mint = game.cc_mint;
+    let multiplier = 20000 - (game.config.house_edge_bps as u32 * 2);
+    let slot = Clock::get()?.slot;
+    let (bet, from_version) = load_versioned(
+        &info.try_borrow_data()?,
+        PlayerBetV0::LEN,
+        |legacy: PlayerBetV0| legacy.upgrade(mint, multiplier, slot),
+    )?;
+    check_upgrade(from_version, PlayerBet::VERSION)?;
+    require!(bet.game == game.key(), CasinoError::Unauthorized);
+
+    write_upgraded(&info, &ctx.accounts.payer, &ctx.accounts.system_program, PlayerBet::LEN, &bet)?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        from_version,
+        to_version: PlayerBet::VERSION,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePull<'info> {
+    /// Pays rent for the grown account (anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Gacha pull result in any supported layout
+    /// CHECK: Owner checked here, discriminator and layout by `load_versioned`
+    #[account(mut, owner = crate::ID)]
+    pub pull_result: UncheckedAccount<'info>,
+
+    /// Game the pulls were made in (already migrated)
+    pub game_state: Account<'info, GameState>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_pull_handler(ctx: Context<MigratePull>) -> Result<()> {
+    let info = ctx.accounts.pull_result.to_account_info();
+    let game = &ctx.accounts.game_state;
+
+    // Legacy pulls were in the primary mint and cost the minimum bet each
+    let mint = game.cc_mint;
+    let cost_per_pull = game.config.min_bet;
+    let slot = Clock::get()?.slot;
+    let (pull_result, from_version) = load_versioned(
+        &info.try_borrow_data()?,
+        GachaPullResultV0::LEN,
+        |legacy: GachaPullResultV0| legacy.upgrade(mint, cost_per_pull, slot),
+    )?;
+    check_upgrade(from_version, GachaPullResult::VERSION)?;
+    require!(pull_result.game == game.key(), CasinoError::Unauthorized);
+
+    write_upgraded(
+        &info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        GachaPullResult::LEN,
+        &pull_result,
+    )?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        from_version,
+        to_version: GachaPullResult::VERSION,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateEscrow<'info> {
+    /// Game treasurer (receives shares for the swept tokens and the escrow's rent)
+    #[account(mut)]
+    pub treasurer: Signer<'info>,
+
+    /// Game whose legacy escrow is swept (already migrated)
+    #[account(
+        mut,
+        has_one = treasurer @ CasinoError::Unauthorized,
+        constraint = game_state.currency == Currency::Token @ CasinoError::WrongCurrency,
+        seeds = [b"game", game_state.slug_as_str().as_bytes()],
+        bump = game_state.escrow_bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Shared bankroll for the game's primary mint
+    #[account(
+        mut,
+        seeds = [b"bankroll", game_state.cc_mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Game's primary mint
+    #[account(address = game_state.cc_mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Legacy per-game escrow (closed once swept)
+    #[account(
+        mut,
+        associated_token::mint = game_state.cc_mint,
+        associated_token::authority = game_state,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared vault
+    #[account(
+        mut,
+        associated_token::mint = game_state.cc_mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Liquidity share mint
+    #[account(
+        mut,
+        address = bankroll.share_mint,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Treasurer's share token account (receives minted shares)
+    #[account(
+        init_if_needed,
+        payer = treasurer,
+        associated_token::mint = share_mint,
+        associated_token::authority = treasurer,
+        associated_token::token_program = token_program,
+    )]
+    pub treasurer_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn escrow_handler(ctx: Context<MigrateEscrow>) -> Result<()> {
+    let game = &ctx.accounts.game_state;
+    let slug = game.slug_as_str();
+    let seeds = &[b"game".as_ref(), slug.as_bytes(), &[game.escrow_bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    // Pending legacy bets are settled from the shared vault after this
+    let amount = ctx.accounts.escrow.amount;
+    let received = amount - transfer_fee(&ctx.accounts.mint, amount)?;
+    if received > 0 {
+        // The swept tokens stay owned by the game, as shares like any deposit
+        let shares = deposit_shares(
+            &ctx.accounts.bankroll,
+            &ctx.accounts.vault,
+            &ctx.accounts.share_mint,
+            received,
+        )?;
+        mint_shares(
+            &ctx.accounts.bankroll,
+            &ctx.accounts.share_mint,
+            &ctx.accounts.treasurer_share_account,
+            &ctx.accounts.token_program,
+            shares,
+        )?;
+    }
+    if amount > 0 {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.escrow.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.game_state.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+    }
+
+    // The escrow can't be swept twice
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.escrow.to_account_info(),
+        destination: ctx.accounts.treasurer.to_account_info(),
+        authority: ctx.accounts.game_state.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::close_account(cpi_ctx)?;
+
+    // The swept tokens back the game's allocation
+    let bankroll = &mut ctx.accounts.bankroll;
+    let game = &mut ctx.accounts.game_state;
+    game.allocation = game.allocation.checked_add(received).unwrap();
+    bankroll.total_allocated = bankroll.total_allocated.checked_add(received).unwrap();
+
+    emit!(EscrowMigrated {
+        game: game.key(),
+        bankroll: bankroll.key(),
+        amount: received,
+        allocation: game.allocation,
+    });
+
+    msg!(
+        "Swept {} tokens from the {} escrow into the shared vault",
+        received,
+        game.slug_as_str()
+    );
+
+    Ok(())
+}
+
+fn check_upgrade(from_version: u8, to_version: u8) -> Result<()> {
+    require!(from_version <= to_version, CasinoError::UnsupportedAccountVersion);
+    require!(from_version < to_version, CasinoError::AccountUpToDate);
+    Ok(())
+}
+
+/// Resize `account` to `len`, keep it rent-exempt and store `state` in it
+fn write_upgraded<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    len: usize,
+    state: &T,
+) -> Result<()> {
+    let shortfall = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    account.realloc(len, true)?;
+    let mut data = account.try_borrow_mut_data()?;
+    state.try_serialize(&mut &mut data[..])
+}
@@ -0,0 +1,134 @@
+//! Additional mint instructions (per-mint escrow and bet limits)
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::{CasinoError, MintAccepted, MintLimitsUpdated};
+
+#[derive(Accounts)]
+pub struct AddGameMint<'info> {
+    /// Game authority (pays for account creation)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+        constraint = game_state.currency == Currency::Token @ CasinoError::WrongCurrency,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Bankroll for the mint being added (its vault escrows the bets)
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Mint config PDA
+    #[account(
+        init,
+        payer = authority,
+        space = MintConfig::LEN,
+        seeds = [b"mint_config", game_state.key().as_ref(), bankroll.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_handler(ctx: Context<AddGameMint>, min_bet: u64, max_bet: u64) -> Result<()> {
+    add_game_mint(
+        &mut ctx.accounts.game_state,
+        &mut ctx.accounts.bankroll,
+        &mut ctx.accounts.mint_config,
+        ctx.bumps.mint_config,
+        min_bet,
+        max_bet,
+    )
+}
+
+/// Accept the bankroll's mint in a game and fill in its new MintConfig
+pub fn add_game_mint(
+    game: &mut Account<GameState>,
+    bankroll: &mut Account<Bankroll>,
+    mint_config: &mut Account<MintConfig>,
+    bump: u8,
+    min_bet: u64,
+    max_bet: u64,
+) -> Result<()> {
+    require!(game.currency == Currency::Token, CasinoError::WrongCurrency);
+    require!(min_bet > 0 && min_bet <= max_bet, CasinoError::InvalidBetLimits);
+
+    let mint = bankroll.mint;
+    require!(!game.accepts_mint(&mint), CasinoError::MintAlreadyAccepted);
+    let index = game.accepted_mint_count as usize;
+    require!(index < MAX_ACCEPTED_MINTS, CasinoError::TooManyMints);
+
+    game.accepted_mints[index] = mint;
+    game.accepted_mint_count += 1;
+
+    bankroll.game_count = bankroll.game_count.checked_add(1).unwrap();
+
+    mint_config.game = game.key();
+    mint_config.mint = mint;
+    mint_config.min_bet = min_bet;
+    mint_config.max_bet = max_bet;
+    mint_config.allocation = 0;
+    mint_config.realized_profit = 0;
+    mint_config.total_volume = 0;
+    mint_config.created_at = Clock::get()?.unix_timestamp;
+    mint_config.version = MintConfig::VERSION;
+    mint_config.bump = bump;
+
+    emit!(MintAccepted {
+        game: game.key(),
+        mint,
+        min_bet,
+        max_bet,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMintLimits<'info> {
+    /// Game authority
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(has_one = authority @ CasinoError::Unauthorized)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Mint config to update
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), mint_config.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+pub fn set_mint_limits_handler(ctx: Context<SetMintLimits>, min_bet: u64, max_bet: u64) -> Result<()> {
+    set_mint_limits(&mut ctx.accounts.mint_config, min_bet, max_bet)
+}
+
+pub fn set_mint_limits(mint_config: &mut Account<MintConfig>, min_bet: u64, max_bet: u64) -> Result<()> {
+    require!(min_bet > 0 && min_bet <= max_bet, CasinoError::InvalidBetLimits);
+
+    mint_config.min_bet = min_bet;
+    mint_config.max_bet = max_bet;
+
+    emit!(MintLimitsUpdated {
+        game: mint_config.game,
+        mint: mint_config.mint,
+        min_bet,
+        max_bet,
+    });
+
+    Ok(())
+}
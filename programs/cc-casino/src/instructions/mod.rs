@@ -1,17 +1,61 @@
 //! Instruction handlers for CC Casino
 
+pub mod global;
+pub mod migrate;
 pub mod initialize;
+pub mod bankroll;
+pub mod balance;
 pub mod fund;
+pub mod native;
+pub mod mint_config;
+pub mod config;
+pub mod pause;
+pub mod authority;
+pub mod roles;
+pub mod council;
+pub mod liquidity;
 pub mod withdraw;
 pub mod coinflip;
 pub mod crash;
 pub mod jackpot;
 pub mod gacha;
+pub mod autobet;
+pub mod refund;
+pub mod cancel;
+pub mod close;
+pub mod throttle;
+pub mod burn;
+pub mod referral;
+pub mod profile;
+pub mod responsible;
+pub mod session;
 
+pub use global::*;
+pub use migrate::*;
 pub use initialize::*;
+pub use bankroll::*;
+pub use balance::*;
 pub use fund::*;
+pub use native::*;
+pub use mint_config::*;
+pub use config::*;
+pub use pause::*;
+pub use authority::*;
+pub use roles::*;
+pub use council::*;
+pub use liquidity::*;
 pub use withdraw::*;
 pub use coinflip::*;
 pub use crash::*;
 pub use jackpot::*;
 pub use gacha::*;
+pub use autobet::*;
+pub use refund::*;
+pub use cancel::*;
+pub use close::*;
+pub use throttle::*;
+pub use burn::*;
+pub use referral::*;
+pub use profile::*;
+pub use responsible::*;
+pub use session::*;
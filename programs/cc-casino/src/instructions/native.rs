@@ -0,0 +1,147 @@
+//! Native SOL vault liquidity instructions
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::transfer::{sol_vault_balance, transfer_from_sol_vault, transfer_to_sol_vault};
+use crate::{CasinoError, SolVaultFunded, SolVaultWithdrawn};
+
+#[derive(Accounts)]
+pub struct FundSolVault<'info> {
+    /// Game treasurer
+    #[account(mut)]
+    pub treasurer: Signer<'info>,
+
+    /// Native SOL game
+    #[account(
+        mut,
+        has_one = treasurer @ CasinoError::Unauthorized,
+        constraint = game_state.currency == Currency::Native @ CasinoError::WrongCurrency,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Native SOL vault
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn fund_handler(ctx: Context<FundSolVault>, amount: u64) -> Result<()> {
+    fund_sol_vault(
+        &mut ctx.accounts.game_state,
+        &ctx.accounts.sol_vault,
+        &ctx.accounts.treasurer.to_account_info(),
+        &ctx.accounts.system_program,
+        amount,
+    )
+}
+
+/// Move lamports from `from` into the game's SOL vault as funded liquidity
+pub fn fund_sol_vault<'info>(
+    game: &mut Account<'info, GameState>,
+    sol_vault: &SystemAccount<'info>,
+    from: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    require!(game.currency == Currency::Native, CasinoError::WrongCurrency);
+
+    transfer_to_sol_vault(system_program, from, sol_vault, amount)?;
+
+    game.allocation = game.allocation.checked_add(amount).unwrap();
+
+    emit!(SolVaultFunded {
+        game: game.key(),
+        amount,
+        allocation: game.allocation,
+    });
+
+    msg!("Funded SOL vault with {} lamports", amount);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSolVault<'info> {
+    /// Game treasurer (receives withdrawal)
+    #[account(mut)]
+    pub treasurer: Signer<'info>,
+
+    /// Native SOL game
+    #[account(
+        mut,
+        has_one = treasurer @ CasinoError::Unauthorized,
+        constraint = game_state.currency == Currency::Native @ CasinoError::WrongCurrency,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Native SOL vault
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn withdraw_sol_vault_handler(ctx: Context<WithdrawSolVault>, amount: u64) -> Result<()> {
+    withdraw_sol_vault(
+        &mut ctx.accounts.game_state,
+        &ctx.accounts.sol_vault,
+        &ctx.accounts.treasurer.to_account_info(),
+        &ctx.accounts.system_program,
+        amount,
+    )
+}
+
+/// Pay lamports out of the game's SOL vault to `to`, up to its free liquidity
+pub fn withdraw_sol_vault<'info>(
+    game: &mut Account<'info, GameState>,
+    sol_vault: &SystemAccount<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    require!(game.currency == Currency::Native, CasinoError::WrongCurrency);
+    require!(
+        amount <= game.available_liquidity(),
+        CasinoError::AllocationExceeded
+    );
+    require!(
+        amount <= sol_vault_balance(sol_vault)?,
+        CasinoError::InsufficientEscrow
+    );
+
+    transfer_from_sol_vault(
+        system_program,
+        game.key(),
+        game.sol_vault_bump,
+        sol_vault,
+        to,
+        amount,
+    )?;
+
+    // Draw down funded liquidity first, then realized profit
+    let from_allocation = amount.min(game.allocation);
+    game.allocation -= from_allocation;
+    game.record_settlement(0, amount - from_allocation);
+
+    emit!(SolVaultWithdrawn {
+        game: game.key(),
+        amount,
+        allocation: game.allocation,
+    });
+
+    msg!("Withdrew {} lamports from SOL vault", amount);
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Per-game pause and resume instructions
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::{CasinoError, GamePaused, GameResumed};
+
+#[derive(Accounts)]
+pub struct SetGameActive<'info> {
+    /// Game authority or operator
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        constraint = game_state.authority == authority.key()
+            || game_state.operator == authority.key() @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn pause_handler(ctx: Context<SetGameActive>) -> Result<()> {
+    pause_game(&mut ctx.accounts.game_state)
+}
+
+pub fn resume_handler(ctx: Context<SetGameActive>) -> Result<()> {
+    resume_game(&mut ctx.accounts.game_state)
+}
+
+/// Stop new bets; resolutions and refunds keep working
+pub fn pause_game(game: &mut Account<GameState>) -> Result<()> {
+    require!(game.is_active, CasinoError::GameNotActive);
+    game.is_active = false;
+
+    emit!(GamePaused { game: game.key() });
+
+    Ok(())
+}
+
+pub fn resume_game(game: &mut Account<GameState>) -> Result<()> {
+    require!(!game.is_active, CasinoError::GameAlreadyActive);
+    game.is_active = true;
+
+    emit!(GameResumed { game: game.key() });
+
+    Ok(())
+}
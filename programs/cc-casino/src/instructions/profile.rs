@@ -0,0 +1,177 @@
+//! Player profile, VIP tier and rakeback instructions
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::*;
+use crate::transfer::transfer_from_vault;
+use crate::{CasinoError, RakebackClaimed, VipTiersUpdated};
+
+#[derive(Accounts)]
+pub struct ConfigureVipTiers<'info> {
+    /// Bankroll authority (pays for account creation)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        has_one = authority @ CasinoError::Unauthorized,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// VIP tier configuration PDA
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = VipConfig::LEN,
+        seeds = [b"vip", bankroll.key().as_ref()],
+        bump
+    )]
+    pub vip_config: Account<'info, VipConfig>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn configure_vip_handler(
+    ctx: Context<ConfigureVipTiers>,
+    thresholds: [u64; VIP_TIERS],
+    rakeback_bps: [u16; VIP_TIERS],
+) -> Result<()> {
+    require!(
+        thresholds.windows(2).all(|pair| pair[0] <= pair[1]),
+        CasinoError::InvalidVipTiers
+    );
+    require!(
+        rakeback_bps.iter().all(|bps| *bps <= 10000),
+        CasinoError::InvalidVipTiers
+    );
+
+    let vip_config = &mut ctx.accounts.vip_config;
+    vip_config.bankroll = ctx.accounts.bankroll.key();
+    vip_config.thresholds = thresholds;
+    vip_config.rakeback_bps = rakeback_bps;
+    vip_config.version = VipConfig::VERSION;
+    vip_config.bump = ctx.bumps.vip_config;
+
+    emit!(VipTiersUpdated {
+        bankroll: vip_config.bankroll,
+        thresholds,
+        rakeback_bps,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRakeback<'info> {
+    /// Player
+    pub player: Signer<'info>,
+
+    /// Player profile
+    #[account(
+        mut,
+        has_one = player,
+        seeds = [b"profile", player.key().as_ref()],
+        bump = player_profile.bump,
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// Shared bankroll
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Bankroll token mint
+    #[account(address = bankroll.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Shared vault
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Player's token account
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_rakeback_handler(ctx: Context<ClaimRakeback>) -> Result<()> {
+    let amount = ctx.accounts.player_profile.rakeback_claimable;
+    require!(amount > 0, CasinoError::NothingToClaim);
+
+    let bankroll = &mut ctx.accounts.bankroll;
+    bankroll.unclaimed_rewards = bankroll.unclaimed_rewards.saturating_sub(amount);
+    require!(
+        bankroll.liquidity(ctx.accounts.vault.amount) >= amount,
+        CasinoError::InsufficientEscrow
+    );
+
+    transfer_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.bankroll,
+        &ctx.accounts.vault,
+        &ctx.accounts.player_token_account,
+        amount,
+    )?;
+
+    let profile = &mut ctx.accounts.player_profile;
+    profile.rakeback_claimable = 0;
+
+    emit!(RakebackClaimed {
+        player: profile.player,
+        amount,
+        total_rakeback: profile.total_rakeback,
+    });
+
+    Ok(())
+}
+
+/// Update the player's profile for a new bet and accrue VIP rakeback on its
+/// house edge. Rakeback is booked as a house payout.
+pub fn record_wager(
+    game: &mut Account<GameState>,
+    bankroll: &mut Account<Bankroll>,
+    profile: &mut Account<PlayerProfile>,
+    vip_config: Option<&Account<VipConfig>>,
+    bet_amount: u64,
+    now: i64,
+) -> Result<()> {
+    // Tier is based on volume before this bet
+    let rakeback_bps = vip_config
+        .map(|vip| vip.rakeback_bps_for(profile.total_wagered))
+        .unwrap_or(0);
+
+    profile.total_wagered = profile.total_wagered.checked_add(bet_amount).unwrap();
+    profile.bet_count = profile.bet_count.checked_add(1).unwrap();
+    profile.last_bet_at = now;
+
+    let house_edge = (bet_amount as u128 * game.config.house_edge_bps as u128) / 10000;
+    let rakeback = ((house_edge * rakeback_bps as u128) / 10000) as u64;
+    if rakeback > 0 {
+        profile.rakeback_claimable = profile.rakeback_claimable.checked_add(rakeback).unwrap();
+        profile.total_rakeback = profile.total_rakeback.checked_add(rakeback).unwrap();
+        bankroll.unclaimed_rewards = bankroll.unclaimed_rewards.checked_add(rakeback).unwrap();
+        game.record_settlement(0, rakeback);
+    }
+
+    Ok(())
+}
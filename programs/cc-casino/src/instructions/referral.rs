@@ -0,0 +1,232 @@
+//! Referral program instructions
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::*;
+use crate::transfer::transfer_from_vault;
+use crate::{CasinoError, ReferralAccrued, ReferralClaimed, ReferrerRegistered};
+
+#[derive(Accounts)]
+pub struct SetReferralShare<'info> {
+    /// Bankroll authority
+    pub authority: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+}
+
+pub fn set_referral_share_handler(ctx: Context<SetReferralShare>, referral_bps: u16) -> Result<()> {
+    require!(referral_bps <= 10000, CasinoError::InvalidReferralShare);
+
+    ctx.accounts.bankroll.referral_bps = referral_bps;
+
+    msg!("Referral share set to {} bps", referral_bps);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    /// Player (signer, pays for account creation)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Player profile PDA
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::LEN,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// Referrer wallet
+    /// CHECK: Only used for key matching
+    pub referrer: AccountInfo<'info>,
+
+    /// Bankroll the referrer earns on
+    #[account(
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Referrer earnings PDA
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = ReferrerAccount::LEN,
+        seeds = [b"referrer", bankroll.key().as_ref(), referrer.key().as_ref()],
+        bump
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_handler(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let profile = &mut ctx.accounts.player_profile;
+    let clock = Clock::get()?;
+
+    require!(!profile.has_referrer(), CasinoError::ReferrerAlreadySet);
+    require!(
+        ctx.accounts.referrer.key() != ctx.accounts.player.key(),
+        CasinoError::InvalidReferrer
+    );
+
+    profile.ensure_initialized(
+        ctx.accounts.player.key(),
+        ctx.bumps.player_profile,
+        clock.unix_timestamp,
+    );
+    profile.referrer = ctx.accounts.referrer.key();
+
+    let referrer_account = &mut ctx.accounts.referrer_account;
+    if referrer_account.referrer == Pubkey::default() {
+        referrer_account.referrer = ctx.accounts.referrer.key();
+        referrer_account.bankroll = ctx.accounts.bankroll.key();
+        referrer_account.version = ReferrerAccount::VERSION;
+        referrer_account.bump = ctx.bumps.referrer_account;
+    }
+    referrer_account.referred_players = referrer_account.referred_players.checked_add(1).unwrap();
+
+    emit!(ReferrerRegistered {
+        player: profile.player,
+        referrer: profile.referrer,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    /// Referrer
+    pub referrer: Signer<'info>,
+
+    /// Shared bankroll
+    #[account(
+        mut,
+        seeds = [b"bankroll", bankroll.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Account<'info, Bankroll>,
+
+    /// Bankroll token mint
+    #[account(address = bankroll.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Referrer earnings PDA
+    #[account(
+        mut,
+        has_one = referrer,
+        has_one = bankroll,
+        seeds = [b"referrer", bankroll.key().as_ref(), referrer.key().as_ref()],
+        bump = referrer_account.bump,
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+
+    /// Shared vault
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Referrer's token account
+    #[account(
+        mut,
+        associated_token::mint = bankroll.mint,
+        associated_token::authority = referrer,
+        associated_token::token_program = token_program,
+    )]
+    pub referrer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_handler(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    let amount = ctx.accounts.referrer_account.claimable;
+    require!(amount > 0, CasinoError::NothingToClaim);
+
+    let bankroll = &mut ctx.accounts.bankroll;
+    bankroll.unclaimed_rewards = bankroll.unclaimed_rewards.saturating_sub(amount);
+    require!(
+        bankroll.liquidity(ctx.accounts.vault.amount) >= amount,
+        CasinoError::InsufficientEscrow
+    );
+
+    transfer_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.bankroll,
+        &ctx.accounts.vault,
+        &ctx.accounts.referrer_token_account,
+        amount,
+    )?;
+
+    let referrer_account = &mut ctx.accounts.referrer_account;
+    referrer_account.claimable = 0;
+    referrer_account.total_claimed = referrer_account.total_claimed.checked_add(amount).unwrap();
+
+    emit!(ReferralClaimed {
+        referrer: referrer_account.referrer,
+        bankroll: referrer_account.bankroll,
+        amount,
+        total_claimed: referrer_account.total_claimed,
+    });
+
+    Ok(())
+}
+
+/// Credit the player's referrer with their share of the house edge on a bet.
+/// The reward is booked as a house payout so realized profit stays accurate.
+pub fn accrue_referral(
+    game: &mut Account<GameState>,
+    bankroll: &mut Account<Bankroll>,
+    profile: &Account<PlayerProfile>,
+    referrer_account: Option<&mut Account<ReferrerAccount>>,
+    bet_amount: u64,
+) -> Result<()> {
+    if !profile.has_referrer() {
+        return Ok(());
+    }
+
+    let referrer_account = referrer_account.ok_or(CasinoError::InvalidReferrer)?;
+    require!(
+        referrer_account.referrer == profile.referrer
+            && referrer_account.bankroll == bankroll.key(),
+        CasinoError::InvalidReferrer
+    );
+
+    let house_edge = (bet_amount as u128 * game.config.house_edge_bps as u128) / 10000;
+    let reward = ((house_edge * bankroll.referral_bps as u128) / 10000) as u64;
+    if reward == 0 {
+        return Ok(());
+    }
+
+    referrer_account.claimable = referrer_account.claimable.checked_add(reward).unwrap();
+    referrer_account.total_earned = referrer_account.total_earned.checked_add(reward).unwrap();
+    bankroll.unclaimed_rewards = bankroll.unclaimed_rewards.checked_add(reward).unwrap();
+    game.record_settlement(0, reward);
+
+    emit!(ReferralAccrued {
+        game: game.key(),
+        player: profile.player,
+        referrer: profile.referrer,
+        amount: reward,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,466 @@
+//! Resolution timeout and refund instructions for bets that were never resolved
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::balance::credit_balance;
+use crate::state::*;
+use crate::transfer::{required, GameFunds};
+use crate::{BetRefunded, CasinoError};
+
+#[derive(Accounts)]
+pub struct SetResolutionTimeout<'info> {
+    /// Authority (must match game authority)
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn set_timeout_handler(ctx: Context<SetResolutionTimeout>, slots: u64) -> Result<()> {
+    set_resolution_timeout(&mut ctx.accounts.game_state, slots)
+}
+
+pub fn set_resolution_timeout(game: &mut Account<GameState>, slots: u64) -> Result<()> {
+    // Too short a timeout would let players refund bets the resolver is about to
+    // settle, and bets can't be resolved before their cancel window closes
+    require!(
+        slots >= MIN_RESOLUTION_TIMEOUT_SLOTS && slots > game.cancel_window_slots,
+        CasinoError::InvalidResolutionTimeout
+    );
+
+    game.resolution_timeout_slots = slots;
+
+    msg!("Resolution timeout for {} set to {} slots", game.slug_as_str(), slots);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundBet<'info> {
+    /// Player (receives the refund)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Game state (returns the platform fee)
+    #[account(mut)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Unresolved coin flip bet
+    #[account(
+        mut,
+        close = rent_receiver,
+        constraint = player_bet.outcome == BetOutcome::Pending @ CasinoError::AlreadyResolved,
+        seeds = [b"bet", game_state.key().as_ref(), player.key().as_ref()],
+        bump = player_bet.bump,
+    )]
+    pub player_bet: Account<'info, PlayerBet>,
+
+    /// Wallet that paid the bet's rent and fee (the player or a relayer)
+    /// CHECK: Matched against the recorded rent payer
+    #[account(mut, address = player_bet.rent_payer @ CasinoError::WrongRecipient)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// Player's token account (token games)
+    #[account(
+        mut,
+        associated_token::mint = player_bet.mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint the bet was placed in (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", player_bet.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint the bet was placed in (token games)
+    #[account(address = player_bet.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = player_bet.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Player's internal balance (required when the stake came from it)
+    #[account(
+        mut,
+        constraint = player_balance.player == player_bet.player
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn refund_bet_handler(ctx: Context<RefundBet>) -> Result<()> {
+    let bet = &ctx.accounts.player_bet;
+    require!(
+        ctx.accounts
+            .game_state
+            .resolution_expired(bet.bet_slot, Clock::get()?.slot),
+        CasinoError::ResolutionPending
+    );
+
+    // Stakes are only realized on resolution, so the ledger is untouched
+    if bet.from_balance {
+        credit_balance(
+            required(ctx.accounts.bankroll.as_deref_mut())?,
+            required(ctx.accounts.player_balance.as_deref_mut())?,
+            bet.bet_amount,
+        )?;
+    } else {
+        GameFunds::new(
+            &ctx.accounts.game_state,
+            ctx.accounts.mint.as_deref(),
+            ctx.accounts.bankroll.as_ref(),
+            ctx.accounts.vault.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            ctx.accounts.sol_vault.as_ref(),
+            &ctx.accounts.system_program,
+        )
+        .pay(
+            &ctx.accounts.player.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            bet.bet_amount,
+        )?;
+    }
+
+    let fee_amount = refund_fee(
+        &mut ctx.accounts.game_state,
+        &ctx.accounts.rent_receiver.to_account_info(),
+        bet.fee_amount,
+    )?;
+
+    emit!(BetRefunded {
+        game: ctx.accounts.game_state.key(),
+        player: ctx.accounts.player.key(),
+        amount: bet.bet_amount,
+        fee_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundGachaPull<'info> {
+    /// Player (receives the refund)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Game state
+    #[account(mut)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Unresolved pull
+    #[account(
+        mut,
+        close = rent_receiver,
+        has_one = player @ CasinoError::Unauthorized,
+        constraint = pull_result.game == game_state.key() @ CasinoError::Unauthorized,
+        constraint = !pull_result.resolved @ CasinoError::AlreadyResolved,
+    )]
+    pub pull_result: Account<'info, GachaPullResult>,
+
+    /// Wallet that paid the pull's rent (the player or a relayer)
+    /// CHECK: Matched against the recorded rent payer
+    #[account(mut, address = pull_result.rent_payer @ CasinoError::WrongRecipient)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// Player's token account (token games)
+    #[account(
+        mut,
+        associated_token::mint = pull_result.mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint the pulls were paid in (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", pull_result.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint the pulls were paid in (token games)
+    #[account(address = pull_result.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = pull_result.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Player's internal balance (required when the stake came from it)
+    #[account(
+        mut,
+        constraint = player_balance.player == pull_result.player
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn refund_pull_handler(ctx: Context<RefundGachaPull>) -> Result<()> {
+    let pull_result = &ctx.accounts.pull_result;
+    require!(
+        ctx.accounts
+            .game_state
+            .resolution_expired(pull_result.pulled_slot, Clock::get()?.slot),
+        CasinoError::ResolutionPending
+    );
+
+    // Stakes are only realized on resolution, so the ledger is untouched
+    if pull_result.from_balance {
+        credit_balance(
+            required(ctx.accounts.bankroll.as_deref_mut())?,
+            required(ctx.accounts.player_balance.as_deref_mut())?,
+            pull_result.total_cost,
+        )?;
+    } else {
+        GameFunds::new(
+            &ctx.accounts.game_state,
+            ctx.accounts.mint.as_deref(),
+            ctx.accounts.bankroll.as_ref(),
+            ctx.accounts.vault.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            ctx.accounts.sol_vault.as_ref(),
+            &ctx.accounts.system_program,
+        )
+        .pay(
+            &ctx.accounts.player.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            pull_result.total_cost,
+        )?;
+    }
+
+    let fee_amount = refund_fee(
+        &mut ctx.accounts.game_state,
+        &ctx.accounts.rent_receiver.to_account_info(),
+        pull_result.fee_amount,
+    )?;
+
+    emit!(BetRefunded {
+        game: ctx.accounts.game_state.key(),
+        player: ctx.accounts.player.key(),
+        amount: pull_result.total_cost,
+        fee_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundRoundEntry<'info> {
+    /// Player (receives the refund)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Game state
+    #[account(mut)]
+    pub game_state: Account<'info, GameState>,
+
+    /// Crash or jackpot round that was never resolved
+    #[account(
+        mut,
+        constraint = round_state.phase != RoundPhase::Ended @ CasinoError::RoundEnded,
+        seeds = [b"round", game_state.key().as_ref(), &round_state.round_number.to_le_bytes()],
+        bump = round_state.bump,
+    )]
+    pub round_state: Account<'info, RoundState>,
+
+    /// Player's entry in the round
+    #[account(
+        mut,
+        close = rent_receiver,
+        constraint = !participant.cashed_out @ CasinoError::AlreadyCashedOut,
+        seeds = [b"participant", round_state.key().as_ref(), player.key().as_ref()],
+        bump = participant.bump,
+    )]
+    pub participant: Account<'info, RoundParticipant>,
+
+    /// Wallet that paid the entry's rent (the player or a relayer)
+    /// CHECK: Matched against the recorded rent payer
+    #[account(mut, address = participant.rent_payer @ CasinoError::WrongRecipient)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// Player's token account (token games)
+    #[account(
+        mut,
+        associated_token::mint = participant.mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared bankroll for the mint the entry was paid in (token games)
+    #[account(
+        mut,
+        seeds = [b"bankroll", participant.mint.as_ref()],
+        bump = bankroll.bump,
+    )]
+    pub bankroll: Option<Account<'info, Bankroll>>,
+
+    /// Mint the entry was paid in (token games)
+    #[account(address = participant.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Shared vault (token games)
+    #[account(
+        mut,
+        associated_token::mint = participant.mint,
+        associated_token::authority = bankroll,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Native SOL vault (native SOL games)
+    #[account(
+        mut,
+        seeds = [b"sol_vault", game_state.key().as_ref()],
+        bump = game_state.sol_vault_bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Ledger of a non-primary mint (required when the entry used one)
+    #[account(
+        mut,
+        seeds = [b"mint_config", game_state.key().as_ref(), participant.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// Player's internal balance (required when the stake came from it)
+    #[account(
+        mut,
+        constraint = player_balance.player == participant.player
+            && bankroll.as_ref().is_some_and(|b| b.key() == player_balance.bankroll)
+            @ CasinoError::WrongBalance,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program (token games)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn refund_entry_handler(ctx: Context<RefundRoundEntry>) -> Result<()> {
+    let round = &mut ctx.accounts.round_state;
+    let participant = &ctx.accounts.participant;
+    require!(
+        ctx.accounts
+            .game_state
+            .resolution_expired(round.started_slot, Clock::get()?.slot),
+        CasinoError::ResolutionPending
+    );
+
+    if participant.from_balance {
+        credit_balance(
+            required(ctx.accounts.bankroll.as_deref_mut())?,
+            required(ctx.accounts.player_balance.as_deref_mut())?,
+            participant.bet_amount,
+        )?;
+    } else {
+        GameFunds::new(
+            &ctx.accounts.game_state,
+            ctx.accounts.mint.as_deref(),
+            ctx.accounts.bankroll.as_ref(),
+            ctx.accounts.vault.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            ctx.accounts.sol_vault.as_ref(),
+            &ctx.accounts.system_program,
+        )
+        .pay(
+            &ctx.accounts.player.to_account_info(),
+            ctx.accounts.player_token_account.as_ref(),
+            participant.bet_amount,
+        )?;
+    }
+
+    // Take the stake back out of wherever it was booked on entry: the round
+    // pool for the primary mint, the MintConfig ledger otherwise
+    if participant.mint == ctx.accounts.game_state.cc_mint {
+        round.pool_size = round.pool_size.saturating_sub(participant.bet_amount);
+    } else {
+        required(ctx.accounts.mint_config.as_deref_mut())?
+            .record_settlement(0, participant.bet_amount);
+    }
+    round.participant_count = round.participant_count.saturating_sub(1);
+
+    let fee_amount = refund_fee(
+        &mut ctx.accounts.game_state,
+        &ctx.accounts.rent_receiver.to_account_info(),
+        participant.fee_amount,
+    )?;
+
+    emit!(BetRefunded {
+        game: ctx.accounts.game_state.key(),
+        player: ctx.accounts.player.key(),
+        amount: participant.bet_amount,
+        fee_amount,
+    });
+
+    Ok(())
+}
+
+/// Return a bet's platform fee from the game account, limited to the fees
+/// still held there above the rent-exempt minimum
+fn refund_fee(game: &mut Account<GameState>, to: &AccountInfo, fee: u64) -> Result<u64> {
+    let game_info = game.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(game_info.data_len());
+    let amount = fee.min(game_info.lamports().saturating_sub(rent_exempt));
+
+    **game_info.try_borrow_mut_lamports()? -= amount;
+    **to.try_borrow_mut_lamports()? += amount;
+    game.total_fees = game.total_fees.saturating_sub(amount);
+
+    Ok(amount)
+}
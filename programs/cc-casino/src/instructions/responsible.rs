@@ -0,0 +1,139 @@
+//! Responsible-gaming instructions (self-exclusion, wager and loss limits)
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::{CasinoError, GamingLimitsUpdated, SelfExcluded};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const SECONDS_PER_WEEK: i64 = 7 * SECONDS_PER_DAY;
+
+#[derive(Accounts)]
+pub struct UpdatePlayerLimits<'info> {
+    /// Player (pays for the profile if it doesn't exist yet)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Player profile PDA
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::LEN,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_gaming_limits_handler(ctx: Context<UpdatePlayerLimits>, limits: GamingLimits) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let profile = &mut ctx.accounts.player_profile;
+    profile.ensure_initialized(ctx.accounts.player.key(), ctx.bumps.player_profile, now);
+    apply_pending_limits(profile, now);
+
+    // Tightening applies right away; anything looser waits out the cool-off
+    profile.limits = profile.limits.strictest(&limits);
+    if profile.limits == limits {
+        profile.pending_limits = GamingLimits::default();
+        profile.pending_limits_at = 0;
+    } else {
+        profile.pending_limits = limits;
+        profile.pending_limits_at = now.checked_add(LIMIT_RAISE_DELAY_SECONDS).unwrap();
+    }
+
+    emit!(GamingLimitsUpdated {
+        player: profile.player,
+        limits: profile.limits,
+        pending_limits: profile.pending_limits,
+        pending_limits_at: profile.pending_limits_at,
+    });
+
+    Ok(())
+}
+
+pub fn self_exclude_handler(ctx: Context<UpdatePlayerLimits>, duration_seconds: i64) -> Result<()> {
+    require!(duration_seconds > 0, CasinoError::InvalidExclusion);
+
+    let now = Clock::get()?.unix_timestamp;
+    let profile = &mut ctx.accounts.player_profile;
+    profile.ensure_initialized(ctx.accounts.player.key(), ctx.bumps.player_profile, now);
+
+    // An exclusion can be extended but never shortened
+    let until = now.saturating_add(duration_seconds);
+    profile.excluded_until = profile.excluded_until.max(until);
+
+    emit!(SelfExcluded {
+        player: profile.player,
+        excluded_until: profile.excluded_until,
+    });
+
+    Ok(())
+}
+
+/// Reject a bet that breaks the player's self-exclusion or limits, then
+/// count it against the current periods. Limits are measured in the primary
+/// bankroll token, so while any are set only `measured` bets are allowed.
+pub fn check_gaming_limits(
+    profile: &mut PlayerProfile,
+    stake: u64,
+    measured: bool,
+    now: i64,
+) -> Result<()> {
+    require!(now >= profile.excluded_until, CasinoError::SelfExcluded);
+
+    apply_pending_limits(profile, now);
+    let limits = profile.limits;
+    if limits.is_empty() {
+        return Ok(());
+    }
+    require!(measured, CasinoError::UnmeteredCurrency);
+
+    // Periods are aligned to the Unix epoch
+    let day_start = now - now.rem_euclid(SECONDS_PER_DAY);
+    if profile.day_start != day_start {
+        profile.day_start = day_start;
+        profile.day_wagered = 0;
+        profile.day_won = 0;
+    }
+    let week_start = now - now.rem_euclid(SECONDS_PER_WEEK);
+    if profile.week_start != week_start {
+        profile.week_start = week_start;
+        profile.week_wagered = 0;
+        profile.week_won = 0;
+    }
+
+    let day_wagered = profile.day_wagered.saturating_add(stake);
+    let week_wagered = profile.week_wagered.saturating_add(stake);
+    require!(
+        within(day_wagered, limits.daily_wager) && within(week_wagered, limits.weekly_wager),
+        CasinoError::WagerLimitExceeded
+    );
+
+    // Assume the stake is lost when checking the loss limits
+    require!(
+        within(day_wagered.saturating_sub(profile.day_won), limits.daily_loss)
+            && within(week_wagered.saturating_sub(profile.week_won), limits.weekly_loss),
+        CasinoError::LossLimitExceeded
+    );
+
+    profile.day_wagered = day_wagered;
+    profile.week_wagered = week_wagered;
+
+    Ok(())
+}
+
+fn within(amount: u64, limit: u64) -> bool {
+    limit == 0 || amount <= limit
+}
+
+/// Promote pending limits once their cool-off has passed
+fn apply_pending_limits(profile: &mut PlayerProfile, now: i64) {
+    if profile.pending_limits_at != 0 && now >= profile.pending_limits_at {
+        profile.limits = profile.pending_limits;
+        profile.pending_limits = GamingLimits::default();
+        profile.pending_limits_at = 0;
+    }
+}
@@ -0,0 +1,42 @@
+//! Delegated game role instructions (operator, resolver, treasurer)
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::{CasinoError, RoleAssigned};
+
+#[derive(Accounts)]
+pub struct AssignRole<'info> {
+    /// Authority (must match game authority)
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn assign_role_handler(ctx: Context<AssignRole>, role: GameRole, holder: Pubkey) -> Result<()> {
+    assign_role(&mut ctx.accounts.game_state, role, holder)
+}
+
+/// Hand a game role to `holder`, replacing the previous holder
+pub fn assign_role(game: &mut Account<GameState>, role: GameRole, holder: Pubkey) -> Result<()> {
+    match role {
+        GameRole::Operator => game.operator = holder,
+        GameRole::Resolver => game.resolver = holder,
+        GameRole::Treasurer => game.treasurer = holder,
+    }
+
+    emit!(RoleAssigned {
+        game: game.key(),
+        role,
+        holder,
+    });
+
+    msg!("{:?} of {} set to {}", role, game.slug_as_str(), holder);
+
+    Ok(())
+}
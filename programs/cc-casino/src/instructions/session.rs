@@ -0,0 +1,159 @@
+//! Session keys: ephemeral signers a player authorizes to bet for them
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::*;
+use crate::{CasinoError, SessionCreated, SessionRevoked};
+
+#[derive(Accounts)]
+pub struct CreateSession<'info> {
+    /// Player wallet (pays for the session and funds the session key)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Ephemeral key being authorized
+    #[account(mut)]
+    pub session_signer: SystemAccount<'info>,
+
+    /// Session PDA
+    #[account(
+        init,
+        payer = player,
+        space = Session::LEN,
+        seeds = [b"session", player.key().as_ref(), session_signer.key().as_ref()],
+        bump
+    )]
+    pub session: Account<'info, Session>,
+
+    /// Mint the session bets in (token games, staked from the player's
+    /// internal balance; omit for native SOL)
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_session_handler(
+    ctx: Context<CreateSession>,
+    games: Vec<Pubkey>,
+    duration_seconds: i64,
+    wager_cap: u64,
+    lamports: u64,
+) -> Result<()> {
+    require!(
+        (1..=MAX_SESSION_GAMES).contains(&games.len())
+            && (1..=MAX_SESSION_DURATION_SECONDS).contains(&duration_seconds)
+            && wager_cap > 0,
+        CasinoError::InvalidSession
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let session = &mut ctx.accounts.session;
+    session.version = Session::VERSION;
+    session.player = ctx.accounts.player.key();
+    session.signer = ctx.accounts.session_signer.key();
+    session.mint = ctx.accounts.mint.as_ref().map(|mint| mint.key()).unwrap_or_default();
+    session.expires_at = now.checked_add(duration_seconds).unwrap();
+    session.wager_cap = wager_cap;
+    session.wagered = 0;
+    session.game_count = games.len() as u8;
+    session.games = [Pubkey::default(); MAX_SESSION_GAMES];
+    session.games[..games.len()].copy_from_slice(&games);
+    session.created_at = now;
+    session.bump = ctx.bumps.session;
+
+    // SOL for the session key's transaction fees, rent and native stakes
+    if lamports > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.player.to_account_info(),
+                    to: ctx.accounts.session_signer.to_account_info(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+
+    emit!(SessionCreated {
+        player: session.player,
+        signer: session.signer,
+        mint: session.mint,
+        expires_at: session.expires_at,
+        wager_cap,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    /// Player wallet (receives the session's rent)
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// Session to end
+    #[account(
+        mut,
+        close = player,
+        has_one = player @ CasinoError::Unauthorized,
+    )]
+    pub session: Account<'info, Session>,
+}
+
+pub fn revoke_handler(ctx: Context<RevokeSession>) -> Result<()> {
+    let session = &ctx.accounts.session;
+
+    emit!(SessionRevoked {
+        player: session.player,
+        signer: session.signer,
+        wagered: session.wagered,
+    });
+
+    Ok(())
+}
+
+/// Check that `signer` may stake `amount` of `mint` on `game` for `player`:
+/// either the wallet itself, or a live session key scoped to the game, whose
+/// stakes count against the session's wager cap. Session keys hold no
+/// authority over the player's tokens, so token stakes must come from the
+/// player's internal balance (`from_balance`).
+pub fn authorize_bet(
+    session: Option<&mut Session>,
+    player: Pubkey,
+    signer: Pubkey,
+    game: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+    from_balance: bool,
+) -> Result<()> {
+    if signer == player {
+        return Ok(());
+    }
+
+    // The session's seeds already bind it to this player and signer
+    let session = session.ok_or(CasinoError::SessionRequired)?;
+    require!(
+        Clock::get()?.unix_timestamp < session.expires_at,
+        CasinoError::SessionExpired
+    );
+    require!(
+        session.covers(&game) && session.mint == mint,
+        CasinoError::SessionScope
+    );
+    require!(
+        amount == 0 || from_balance || mint == Pubkey::default(),
+        CasinoError::SessionBalanceRequired
+    );
+
+    let wagered = session
+        .wagered
+        .checked_add(amount)
+        .filter(|wagered| *wagered <= session.wager_cap)
+        .ok_or(CasinoError::SessionCapExceeded)?;
+    session.wagered = wagered;
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Per-player bet throttling (cooldown and bets per window)
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::CasinoError;
+
+#[derive(Accounts)]
+pub struct SetBetThrottle<'info> {
+    /// Authority (must match game authority)
+    pub authority: Signer<'info>,
+
+    /// Game state
+    #[account(
+        mut,
+        has_one = authority @ CasinoError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn set_handler(
+    ctx: Context<SetBetThrottle>,
+    bet_window_seconds: u32,
+    max_bets_per_window: u16,
+) -> Result<()> {
+    set_bet_throttle(&mut ctx.accounts.game_state, bet_window_seconds, max_bets_per_window)
+}
+
+pub fn set_bet_throttle(
+    game: &mut Account<GameState>,
+    bet_window_seconds: u32,
+    max_bets_per_window: u16,
+) -> Result<()> {
+    require!(
+        max_bets_per_window == 0 || bet_window_seconds > 0,
+        CasinoError::InvalidBetThrottle
+    );
+
+    game.bet_window_seconds = bet_window_seconds;
+    game.max_bets_per_window = max_bets_per_window;
+
+    msg!(
+        "Bet throttle for {} set to {} bets per {}s",
+        game.slug_as_str(),
+        max_bets_per_window,
+        bet_window_seconds
+    );
+
+    Ok(())
+}
+
+/// Enforce the game's cooldown and bets-per-window limit for a player, then
+/// record the bet
+pub fn throttle_bet(
+    game: &Account<GameState>,
+    throttle: &mut PlayerThrottle,
+    player: Pubkey,
+    bump: u8,
+    now: i64,
+) -> Result<()> {
+    if throttle.player == Pubkey::default() {
+        throttle.version = PlayerThrottle::VERSION;
+        throttle.player = player;
+        throttle.game = game.key();
+        throttle.bump = bump;
+    } else {
+        require!(
+            now >= throttle
+                .last_bet_at
+                .saturating_add(game.config.cooldown_seconds as i64),
+            CasinoError::CooldownActive
+        );
+    }
+
+    if game.max_bets_per_window > 0 {
+        if now >= throttle
+            .window_start
+            .saturating_add(game.bet_window_seconds as i64)
+        {
+            throttle.window_start = now;
+            throttle.window_bets = 0;
+        }
+        require!(
+            throttle.window_bets < game.max_bets_per_window,
+            CasinoError::CooldownActive
+        );
+        throttle.window_bets += 1;
+    }
+
+    throttle.last_bet_at = now;
+
+    Ok(())
+}
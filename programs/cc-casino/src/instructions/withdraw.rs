@@ -1,75 +1,50 @@
 //! Withdraw fees instruction
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
 use crate::state::*;
 use crate::CasinoError;
 
 #[derive(Accounts)]
 pub struct WithdrawFees<'info> {
-    /// Authority (must match game authority)
+    /// Game treasurer (receives withdrawal)
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub treasurer: Signer<'info>,
 
-    /// Game state
+    /// Game state (holds platform fees in SOL)
     #[account(
-        has_one = authority @ CasinoError::Unauthorized,
+        mut,
+        has_one = treasurer @ CasinoError::Unauthorized,
         seeds = [b"game", game_state.slug_as_str().as_bytes()],
         bump = game_state.escrow_bump,
     )]
     pub game_state: Account<'info, GameState>,
-
-    /// Game escrow token account
-    #[account(
-        mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = game_state,
-    )]
-    pub escrow: Account<'info, TokenAccount>,
-
-    /// Authority's token account (receives withdrawal)
-    #[account(
-        mut,
-        associated_token::mint = game_state.cc_mint,
-        associated_token::authority = authority,
-    )]
-    pub authority_token_account: Account<'info, TokenAccount>,
-
-    /// Token program
-    pub token_program: Program<'info, Token>,
 }
 
-pub fn handler(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
-    let game = &ctx.accounts.game_state;
-
-    // Get signer seeds for escrow PDA
-    let slug = game.slug_as_str();
-    let seeds = &[
-        b"game".as_ref(),
-        slug.as_bytes(),
-        &[game.escrow_bump],
-    ];
-    let signer_seeds = &[&seeds[..]];
+pub fn withdraw_fees_handler(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    withdraw_fees(
+        &ctx.accounts.game_state,
+        &ctx.accounts.treasurer.to_account_info(),
+        amount,
+    )
+}
 
-    // Transfer from escrow to authority
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.escrow.to_account_info(),
-        to: ctx.accounts.authority_token_account.to_account_info(),
-        authority: ctx.accounts.game_state.to_account_info(),
-    };
+/// Move platform fees (SOL) out of a game account
+pub fn withdraw_fees(game: &Account<GameState>, to: &AccountInfo, amount: u64) -> Result<()> {
+    // Token liquidity belongs to bankroll shareholders; only platform fees
+    // collected in SOL above the rent-exempt minimum can be withdrawn here
+    let game_info = game.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(game_info.data_len());
+    let available = game_info.lamports().saturating_sub(rent_exempt);
+    require!(amount <= available, CasinoError::InsufficientFees);
 
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        cpi_accounts,
-        signer_seeds,
-    );
-    token::transfer(cpi_ctx, amount)?;
+    **game_info.try_borrow_mut_lamports()? -= amount;
+    **to.try_borrow_mut_lamports()? += amount;
 
     msg!(
-        "Withdrew {} tokens from escrow. Remaining: {}",
+        "Withdrew {} lamports of fees. Remaining: {}",
         amount,
-        ctx.accounts.escrow.amount - amount
+        available - amount
     );
 
     Ok(())
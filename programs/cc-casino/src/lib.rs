@@ -6,14 +6,16 @@
 //! - Jackpot: Pool bets, one winner takes all
 //! - Gacha: Pull for tiered prizes
 //!
+//! Games are wagered in $CC (or any SPL / Token-2022 mint with a bankroll)
+//! or in native SOL.
+//!
 //! All games use Switchboard VRF for provably fair randomness.
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use anchor_spl::associated_token::AssociatedToken;
 
 pub mod state;
 pub mod instructions;
+pub mod transfer;
 
 use state::*;
 use instructions::*;
@@ -26,24 +28,207 @@ pub mod cc_casino {
 
     // ============ INITIALIZATION ============
 
-    /// Initialize a new game with given configuration
+    /// Create the program-wide config and game registry (program upgrade authority only)
+    pub fn initialize_global(ctx: Context<InitializeGlobal>) -> Result<()> {
+        instructions::global::initialize_global_handler(ctx)
+    }
+
+    /// Allow a wallet to create games (admin only)
+    pub fn add_game_creator(ctx: Context<AddGameCreator>, creator: Pubkey) -> Result<()> {
+        instructions::global::add_creator_handler(ctx, creator)
+    }
+
+    /// Revoke a wallet's permission to create games (admin only)
+    pub fn remove_game_creator(ctx: Context<RemoveGameCreator>) -> Result<()> {
+        instructions::global::remove_creator_handler(ctx)
+    }
+
+    /// Create the shared bankroll, vault and liquidity share mint for a token mint
+    pub fn initialize_bankroll(
+        ctx: Context<InitializeBankroll>,
+        withdrawal_delay: i64,
+    ) -> Result<()> {
+        instructions::bankroll::initialize_bankroll_handler(ctx, withdrawal_delay)
+    }
+
+    /// Set how much of the shared bankroll a game may draw on (authority only)
+    pub fn rebalance_allocation(
+        ctx: Context<RebalanceAllocation>,
+        allocation: u64,
+    ) -> Result<()> {
+        instructions::bankroll::rebalance_handler(ctx, allocation)
+    }
+
+    /// Initialize and register a new game (admin or allowlisted creators only),
+    /// wagered in the bankroll's token or in native SOL
     pub fn initialize_game(
         ctx: Context<InitializeGame>,
         game_type: GameType,
         slug: String,
         config: GameConfig,
+        currency: Currency,
+        config_delay: i64,
+    ) -> Result<()> {
+        instructions::initialize::initialize_game_handler(ctx, game_type, slug, config, currency, config_delay)
+    }
+
+    /// Propose a new game config, applicable after the game's config delay
+    pub fn propose_config(
+        ctx: Context<ProposeConfig>,
+        config: GameConfig,
+        config_delay: i64,
+    ) -> Result<()> {
+        instructions::config::propose_config_handler(ctx, config, config_delay)
+    }
+
+    /// Apply a proposed config once its delay has passed (authority only)
+    pub fn apply_config(ctx: Context<ApplyConfig>) -> Result<()> {
+        instructions::config::apply_handler(ctx)
+    }
+
+    /// Stop new bets on a game (authority or operator)
+    pub fn pause_game(ctx: Context<SetGameActive>) -> Result<()> {
+        instructions::pause::pause_handler(ctx)
+    }
+
+    /// Reopen a paused game for bets (authority or operator)
+    pub fn resume_game(ctx: Context<SetGameActive>) -> Result<()> {
+        instructions::pause::resume_handler(ctx)
+    }
+
+    /// Nominate a new game authority (current authority only)
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Pubkey,
     ) -> Result<()> {
-        instructions::initialize::handler(ctx, game_type, slug, config)
+        instructions::authority::propose_authority_handler(ctx, new_authority)
+    }
+
+    /// Take over a game as its nominated authority (delegated roles revert to it)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::authority::accept_handler(ctx)
+    }
+
+    /// Assign the operator, resolver or treasurer role of a game (authority only)
+    pub fn assign_role(ctx: Context<AssignRole>, role: GameRole, holder: Pubkey) -> Result<()> {
+        instructions::roles::assign_role_handler(ctx, role, holder)
+    }
+
+    /// Engage or release the program-wide emergency stop (admin only)
+    pub fn set_emergency_stop(ctx: Context<SetEmergencyStop>, paused: bool) -> Result<()> {
+        instructions::global::set_emergency_stop_handler(ctx, paused)
     }
 
-    /// Fund the game's reward pool
+    /// Accept an additional mint in a game, escrowed by that mint's bankroll
+    pub fn add_game_mint(ctx: Context<AddGameMint>, min_bet: u64, max_bet: u64) -> Result<()> {
+        instructions::mint_config::add_handler(ctx, min_bet, max_bet)
+    }
+
+    /// Update bet limits for an additional mint (authority only)
+    pub fn set_mint_limits(
+        ctx: Context<SetMintLimits>,
+        min_bet: u64,
+        max_bet: u64,
+    ) -> Result<()> {
+        instructions::mint_config::set_mint_limits_handler(ctx, min_bet, max_bet)
+    }
+
+    /// Deposit liquidity into the shared bankroll in exchange for share tokens
     pub fn fund_pool(ctx: Context<FundPool>, amount: u64) -> Result<()> {
-        instructions::fund::handler(ctx, amount)
+        instructions::fund::fund_pool_handler(ctx, amount)
     }
 
-    /// Withdraw fees from game (authority only)
+    /// Issue shares for bankroll liquidity nobody holds shares in (authority only)
+    pub fn seed_shares(ctx: Context<SeedShares>) -> Result<()> {
+        instructions::fund::seed_handler(ctx)
+    }
+
+    /// Add liquidity to a native SOL game's vault (treasurer only)
+    pub fn fund_sol_vault(ctx: Context<FundSolVault>, amount: u64) -> Result<()> {
+        instructions::native::fund_handler(ctx, amount)
+    }
+
+    /// Withdraw liquidity or profit from a native SOL game's vault (treasurer only)
+    pub fn withdraw_sol_vault(ctx: Context<WithdrawSolVault>, amount: u64) -> Result<()> {
+        instructions::native::withdraw_sol_vault_handler(ctx, amount)
+    }
+
+    /// Queue a redemption of bankroll shares (executable after the delay)
+    pub fn request_withdrawal(ctx: Context<RequestWithdrawal>, shares: u64) -> Result<()> {
+        instructions::liquidity::request_handler(ctx, shares)
+    }
+
+    /// Redeem queued shares for their current share of the vault
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+        instructions::liquidity::execute_withdrawal_handler(ctx)
+    }
+
+    /// Withdraw platform fees (SOL) from a game (treasurer only)
     pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
-        instructions::withdraw::handler(ctx, amount)
+        instructions::withdraw::withdraw_fees_handler(ctx, amount)
+    }
+
+    // ============ COUNCIL ============
+
+    /// Create an M-of-N council that can own games
+    pub fn create_council(
+        ctx: Context<CreateCouncil>,
+        create_key: Pubkey,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::council::create_council_handler(ctx, create_key, members, threshold)
+    }
+
+    /// Propose a privileged action on a council-owned game (members only)
+    pub fn create_council_proposal(
+        ctx: Context<CreateCouncilProposal>,
+        action: CouncilAction,
+    ) -> Result<()> {
+        instructions::council::create_council_proposal_handler(ctx, action)
+    }
+
+    /// Approve a council proposal (members only)
+    pub fn approve_council_proposal(ctx: Context<ApproveCouncilProposal>) -> Result<()> {
+        instructions::council::approve_handler(ctx)
+    }
+
+    /// Execute a council proposal that reached its threshold (members only)
+    pub fn execute_council_proposal(ctx: Context<ExecuteCouncilProposal>) -> Result<()> {
+        instructions::council::execute_council_proposal_handler(ctx)
+    }
+
+    // ============ MIGRATION ============
+
+    /// Upgrade a game account stored in an older layout (anyone, payer covers rent)
+    pub fn migrate_game(ctx: Context<MigrateGame>) -> Result<()> {
+        instructions::migrate::game_handler(ctx)
+    }
+
+    /// Upgrade a round account stored in an older layout
+    pub fn migrate_round(ctx: Context<MigrateRound>) -> Result<()> {
+        instructions::migrate::round_handler(ctx)
+    }
+
+    /// Upgrade a round participant stored in an older layout (after its round and game)
+    pub fn migrate_participant(ctx: Context<MigrateParticipant>) -> Result<()> {
+        instructions::migrate::participant_handler(ctx)
+    }
+
+    /// Upgrade a player bet stored in an older layout (after its game)
+    pub fn migrate_bet(ctx: Context<MigrateBet>) -> Result<()> {
+        instructions::migrate::bet_handler(ctx)
+    }
+
+    /// Upgrade a gacha pull result stored in an older layout (after its game)
+    pub fn migrate_pull(ctx: Context<MigratePull>) -> Result<()> {
+        instructions::migrate::migrate_pull_handler(ctx)
+    }
+
+    /// Sweep a game's legacy per-game escrow into the shared vault for shares
+    /// and add it to the game's allocation (treasurer only, once per game)
+    pub fn migrate_escrow(ctx: Context<MigrateEscrow>) -> Result<()> {
+        instructions::migrate::escrow_handler(ctx)
     }
 
     // ============ COIN FLIP ============
@@ -62,16 +247,21 @@ pub mod cc_casino {
         ctx: Context<ResolveCoinflip>,
         vrf_result: [u8; 32],
     ) -> Result<()> {
-        instructions::coinflip::resolve_handler(ctx, vrf_result)
+        instructions::coinflip::resolve_coinflip_handler(ctx, vrf_result)
     }
 
     // ============ CRASH ============
 
-    /// Start a new crash round
+    /// Start a new crash round (operator only)
     pub fn start_crash_round(ctx: Context<StartCrashRound>) -> Result<()> {
         instructions::crash::start_round_handler(ctx)
     }
 
+    /// Close betting on a crash round once its window has passed (operator only)
+    pub fn advance_crash_round(ctx: Context<AdvanceCrashRound>) -> Result<()> {
+        instructions::crash::advance_round_handler(ctx)
+    }
+
     /// Join an active crash round with a bet
     pub fn join_crash(ctx: Context<JoinCrash>, bet_amount: u64) -> Result<()> {
         instructions::crash::join_handler(ctx, bet_amount)
@@ -84,7 +274,7 @@ pub mod cc_casino {
 
     /// Resolve crash round with VRF result (determines crash point)
     pub fn resolve_crash(ctx: Context<ResolveCrash>, vrf_result: [u8; 32]) -> Result<()> {
-        instructions::crash::resolve_handler(ctx, vrf_result)
+        instructions::crash::resolve_crash_handler(ctx, vrf_result)
     }
 
     // ============ JACKPOT ============
@@ -103,12 +293,210 @@ pub mod cc_casino {
 
     /// Pull gacha (single or multi-pull)
     pub fn pull_gacha(ctx: Context<PullGacha>, pulls: u8) -> Result<()> {
-        instructions::gacha::pull_handler(ctx, pulls)
+        instructions::gacha::pull_gacha_handler(ctx, pulls)
     }
 
     /// Resolve gacha pulls with VRF result
     pub fn resolve_gacha(ctx: Context<ResolveGacha>, vrf_result: [u8; 32]) -> Result<()> {
-        instructions::gacha::resolve_handler(ctx, vrf_result)
+        instructions::gacha::resolve_gacha_handler(ctx, vrf_result)
+    }
+
+    // ============ PLAYER BALANCES ============
+
+    /// Deposit tokens into the player's internal balance for a bankroll
+    pub fn deposit_balance(ctx: Context<DepositBalance>, amount: u64) -> Result<()> {
+        instructions::balance::deposit_handler(ctx, amount)
+    }
+
+    /// Withdraw tokens from the player's internal balance
+    pub fn withdraw_balance(ctx: Context<WithdrawBalance>, amount: u64) -> Result<()> {
+        instructions::balance::withdraw_balance_handler(ctx, amount)
+    }
+
+    // ============ AUTO-BET ============
+
+    /// Open a coin flip auto-bet session funded from the player's internal balance
+    pub fn open_autobet(
+        ctx: Context<OpenAutoBet>,
+        choice: CoinChoice,
+        strategy: AutoBetStrategy,
+    ) -> Result<()> {
+        instructions::autobet::open_handler(ctx, choice, strategy)
+    }
+
+    /// Place and settle the session's next bet (resolver only)
+    pub fn autobet_step(ctx: Context<AutoBetStep>, vrf_result: [u8; 32]) -> Result<()> {
+        instructions::autobet::step_handler(ctx, vrf_result)
+    }
+
+    /// Stop an auto-bet session and reclaim its rent and unused fees
+    pub fn close_autobet(ctx: Context<CloseAutoBet>) -> Result<()> {
+        instructions::autobet::close_handler(ctx)
+    }
+
+    // ============ THROTTLING ============
+
+    /// Limit how many bets a player may place per time window (authority only)
+    pub fn set_bet_throttle(
+        ctx: Context<SetBetThrottle>,
+        bet_window_seconds: u32,
+        max_bets_per_window: u16,
+    ) -> Result<()> {
+        instructions::throttle::set_handler(ctx, bet_window_seconds, max_bets_per_window)
+    }
+
+    // ============ REFUNDS ============
+
+    /// Set how many slots a bet or round may stay unresolved before refunds open (authority only)
+    pub fn set_resolution_timeout(ctx: Context<SetResolutionTimeout>, slots: u64) -> Result<()> {
+        instructions::refund::set_timeout_handler(ctx, slots)
+    }
+
+    /// Reclaim the stake and platform fee of a coin flip bet that was never resolved
+    pub fn refund_bet(ctx: Context<RefundBet>) -> Result<()> {
+        instructions::refund::refund_bet_handler(ctx)
+    }
+
+    /// Reclaim the cost of gacha pulls that were never resolved
+    pub fn refund_gacha_pull(ctx: Context<RefundGachaPull>) -> Result<()> {
+        instructions::refund::refund_pull_handler(ctx)
+    }
+
+    /// Reclaim a crash or jackpot entry from a round that was never resolved
+    pub fn refund_round_entry(ctx: Context<RefundRoundEntry>) -> Result<()> {
+        instructions::refund::refund_entry_handler(ctx)
+    }
+
+    // ============ CANCELLATION ============
+
+    /// Set the slot window and fee for cancelling pending bets (authority only)
+    pub fn set_cancel_policy(
+        ctx: Context<SetCancelPolicy>,
+        cancel_window_slots: u64,
+        cancel_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::cancel::set_policy_handler(ctx, cancel_window_slots, cancel_fee_bps)
+    }
+
+    /// Cancel a pending coin flip bet within the cancel window
+    pub fn cancel_bet(ctx: Context<CancelBet>) -> Result<()> {
+        instructions::cancel::cancel_bet_handler(ctx)
+    }
+
+    /// Cancel a pending gacha pull within the cancel window
+    pub fn cancel_gacha_pull(ctx: Context<CancelGachaPull>) -> Result<()> {
+        instructions::cancel::cancel_pull_handler(ctx)
+    }
+
+    // ============ CLOSING ============
+
+    /// Close a resolved coin flip bet, returning its rent to the player
+    pub fn close_bet(ctx: Context<CloseBet>) -> Result<()> {
+        instructions::close::close_bet_handler(ctx)
+    }
+
+    /// Close a resolved gacha pull, returning its rent to the player
+    pub fn close_gacha_pull(ctx: Context<CloseGachaPull>) -> Result<()> {
+        instructions::close::close_pull_handler(ctx)
+    }
+
+    /// Close an entry in an ended round, returning its rent to the player
+    pub fn close_round_entry(ctx: Context<CloseRoundEntry>) -> Result<()> {
+        instructions::close::close_entry_handler(ctx)
+    }
+
+    /// Close a round with no entries left, returning its rent to whoever paid it
+    pub fn close_round(ctx: Context<CloseRound>) -> Result<()> {
+        instructions::close::close_round_handler(ctx)
+    }
+
+    /// Close settled bets and pulls in bulk, each back to its player (crank)
+    pub fn close_settled_bets<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseSettledBets<'info>>,
+    ) -> Result<()> {
+        instructions::close::close_bets_handler(ctx)
+    }
+
+    /// Close entries of an ended round in bulk, each back to its player (crank)
+    pub fn close_round_entries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseRoundEntries<'info>>,
+    ) -> Result<()> {
+        instructions::close::close_entries_handler(ctx)
+    }
+
+    // ============ REFERRALS ============
+
+    /// Set the share of house edge credited to referrers (bankroll authority only)
+    pub fn set_referral_share(ctx: Context<SetReferralShare>, referral_bps: u16) -> Result<()> {
+        instructions::referral::set_referral_share_handler(ctx, referral_bps)
+    }
+
+    /// Register the player's referrer (once per player)
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        instructions::referral::register_handler(ctx)
+    }
+
+    /// Claim accrued referral rewards from the bankroll
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        instructions::referral::claim_handler(ctx)
+    }
+
+    // ============ PROFILES & VIP ============
+
+    /// Set VIP volume thresholds and rakeback rates (bankroll authority only)
+    pub fn configure_vip_tiers(
+        ctx: Context<ConfigureVipTiers>,
+        thresholds: [u64; VIP_TIERS],
+        rakeback_bps: [u16; VIP_TIERS],
+    ) -> Result<()> {
+        instructions::profile::configure_vip_handler(ctx, thresholds, rakeback_bps)
+    }
+
+    /// Claim accrued VIP rakeback from the bankroll
+    pub fn claim_rakeback(ctx: Context<ClaimRakeback>) -> Result<()> {
+        instructions::profile::claim_rakeback_handler(ctx)
+    }
+
+    // ============ RESPONSIBLE GAMING ============
+
+    /// Set daily/weekly wager and loss limits; loosening waits out a cool-off
+    pub fn set_gaming_limits(ctx: Context<UpdatePlayerLimits>, limits: GamingLimits) -> Result<()> {
+        instructions::responsible::set_gaming_limits_handler(ctx, limits)
+    }
+
+    /// Refuse all of the player's bets for a period (can only be extended)
+    pub fn self_exclude(ctx: Context<UpdatePlayerLimits>, duration_seconds: i64) -> Result<()> {
+        instructions::responsible::self_exclude_handler(ctx, duration_seconds)
+    }
+
+    // ============ SESSIONS ============
+
+    /// Authorize an ephemeral key to bet for the player within a cap, expiry and game scope
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        games: Vec<Pubkey>,
+        duration_seconds: i64,
+        wager_cap: u64,
+        lamports: u64,
+    ) -> Result<()> {
+        instructions::session::create_session_handler(ctx, games, duration_seconds, wager_cap, lamports)
+    }
+
+    /// End a session and reclaim its rent
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        instructions::session::revoke_handler(ctx)
+    }
+
+    // ============ BURN ============
+
+    /// Set the share of realized house profit burned by the crank (authority only)
+    pub fn set_burn_share(ctx: Context<SetBurnShare>, burn_bps: u16) -> Result<()> {
+        instructions::burn::set_burn_share_handler(ctx, burn_bps)
+    }
+
+    /// Burn the configured share of newly realized house profit from the vault
+    pub fn burn_house_profit(ctx: Context<BurnHouseProfit>) -> Result<()> {
+        instructions::burn::burn_handler(ctx)
     }
 }
 
@@ -160,6 +548,213 @@ pub enum CasinoError {
 
     #[msg("Cooldown active - wait before next bet")]
     CooldownActive,
+
+    #[msg("Burn share exceeds 10000 bps")]
+    InvalidBurnShare,
+
+    #[msg("No realized profit to burn")]
+    NothingToBurn,
+
+    #[msg("Payout exceeds the game's bankroll allocation")]
+    AllocationExceeded,
+
+    #[msg("Game does not draw on this bankroll")]
+    WrongBankroll,
+
+    #[msg("Withdrawal delay must not be negative")]
+    InvalidWithdrawalDelay,
+
+    #[msg("Amount too small to mint or redeem shares")]
+    DepositTooSmall,
+
+    #[msg("Withdrawal request is still time-locked")]
+    WithdrawalLocked,
+
+    #[msg("Insufficient platform fees to withdraw")]
+    InsufficientFees,
+
+    #[msg("Referral share exceeds 10000 bps")]
+    InvalidReferralShare,
+
+    #[msg("Referrer already registered")]
+    ReferrerAlreadySet,
+
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+
+    #[msg("VIP thresholds must ascend and rakeback must not exceed 10000 bps")]
+    InvalidVipTiers,
+
+    #[msg("Transfer fee calculation overflowed")]
+    TransferFeeOverflow,
+
+    #[msg("Account required by the game's currency is missing")]
+    MissingCurrencyAccount,
+
+    #[msg("Instruction not supported for this game's currency")]
+    WrongCurrency,
+
+    #[msg("Mint not accepted by this game")]
+    MintNotAccepted,
+
+    #[msg("Mint already accepted by this game")]
+    MintAlreadyAccepted,
+
+    #[msg("Game already accepts the maximum number of mints")]
+    TooManyMints,
+
+    #[msg("Bet limits must satisfy 0 < min_bet <= max_bet")]
+    InvalidBetLimits,
+
+    #[msg("House edge must be below 10000 bps")]
+    InvalidHouseEdge,
+
+    #[msg("Config change is still time-locked")]
+    ConfigLocked,
+
+    #[msg("Config delay must not be negative")]
+    InvalidConfigDelay,
+
+    #[msg("Game is already active")]
+    GameAlreadyActive,
+
+    #[msg("Emergency stop is active")]
+    EmergencyStop,
+
+    #[msg("Council needs 1-10 distinct members and a threshold between 1 and the member count")]
+    InvalidCouncil,
+
+    #[msg("Signer is not a council member")]
+    NotCouncilMember,
+
+    #[msg("Member already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Proposal already executed")]
+    ProposalExecuted,
+
+    #[msg("Proposal has not reached the approval threshold")]
+    ThresholdNotMet,
+
+    #[msg("Recipient does not match the proposal")]
+    WrongRecipient,
+
+    #[msg("Wallet is not allowed to create games")]
+    CreatorNotAllowed,
+
+    #[msg("Slug must be at most 32 bytes")]
+    SlugTooLong,
+
+    #[msg("Minimum bet must be greater than zero")]
+    ZeroMinBet,
+
+    #[msg("Coin flip house edge must be below 5000 bps")]
+    CoinflipEdgeTooHigh,
+
+    #[msg("Jackpot ticket price (min_bet) must be greater than zero")]
+    ZeroTicketPrice,
+
+    #[msg("Account layout version is newer than this program supports")]
+    UnsupportedAccountVersion,
+
+    #[msg("Account is already in the current layout")]
+    AccountUpToDate,
+
+    #[msg("Bet is still within its resolution timeout")]
+    ResolutionPending,
+
+    #[msg("Resolution timeout is below the minimum")]
+    InvalidResolutionTimeout,
+
+    #[msg("A bet limit per window needs a non-zero window")]
+    InvalidBetThrottle,
+
+    #[msg("Player is self-excluded")]
+    SelfExcluded,
+
+    #[msg("Bet exceeds the player's wager limit")]
+    WagerLimitExceeded,
+
+    #[msg("Bet could exceed the player's loss limit")]
+    LossLimitExceeded,
+
+    #[msg("Gaming limits only allow bets in the primary bankroll token")]
+    UnmeteredCurrency,
+
+    #[msg("Exclusion period must be positive")]
+    InvalidExclusion,
+
+    #[msg("Bet or round has not been resolved")]
+    NotResolved,
+
+    #[msg("Round still has entries to close or refund")]
+    RoundHasEntries,
+
+    #[msg("Only the owner may close this account before the grace period ends")]
+    CloseTooEarly,
+
+    #[msg("Remaining accounts are malformed")]
+    InvalidRemainingAccounts,
+
+    #[msg("Session needs 1-4 games, a positive wager cap and a duration of at most 7 days")]
+    InvalidSession,
+
+    #[msg("Signer is neither the player nor an authorized session key")]
+    SessionRequired,
+
+    #[msg("Session has expired")]
+    SessionExpired,
+
+    #[msg("Session is not authorized for this game or mint")]
+    SessionScope,
+
+    #[msg("Bet exceeds the session's wager cap")]
+    SessionCapExceeded,
+
+    #[msg("Insufficient internal balance")]
+    InsufficientBalance,
+
+    #[msg("Balance account does not belong to this player and bankroll")]
+    WrongBalance,
+
+    #[msg("Auto-bet needs a base bet within the bet limits and 1-1000 bets")]
+    InvalidAutoBet,
+
+    #[msg("Auto-bet session has ended")]
+    AutoBetInactive,
+
+    #[msg("Cancel fee must not exceed 10000 bps and the window must be shorter than the resolution timeout")]
+    InvalidCancelPolicy,
+
+    #[msg("Bet can no longer be cancelled")]
+    CancelWindowClosed,
+
+    #[msg("Bet can't be resolved while it can still be cancelled")]
+    CancelWindowOpen,
+
+    #[msg("Withdrawal would leave game allocations unbacked")]
+    LiquidityAllocated,
+
+    #[msg("Bankroll holds liquidity without shares; seed shares first")]
+    SharesNotSeeded,
+
+    #[msg("Bankroll has shares or no liquidity to seed")]
+    SharesAlreadySeeded,
+
+    #[msg("No config proposal is pending for this game")]
+    NoPendingConfig,
+
+    #[msg("Session keys can only stake tokens from the player's internal balance")]
+    SessionBalanceRequired,
+
+    #[msg("Jackpot entries and draws need at least one ticket")]
+    NoTickets,
+
+    #[msg("Round betting window has not ended yet")]
+    BettingStillOpen,
 }
 
 // ============ EVENTS ============
@@ -170,6 +765,284 @@ pub struct GameInitialized {
     pub game_type: GameType,
     pub slug: String,
     pub authority: Pubkey,
+    pub currency: Currency,
+    pub registry_index: u64,
+}
+
+#[event]
+pub struct CreatorAdded {
+    pub creator: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct CreatorRemoved {
+    pub creator: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct BankrollInitialized {
+    pub bankroll: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub share_mint: Pubkey,
+}
+
+#[event]
+pub struct LiquidityDeposited {
+    pub bankroll: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct SharesSeeded {
+    pub bankroll: Pubkey,
+    pub authority: Pubkey,
+    pub shares: u64,
+}
+
+#[event]
+pub struct WithdrawalRequested {
+    pub bankroll: Pubkey,
+    pub owner: Pubkey,
+    pub shares: u64,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct WithdrawalExecuted {
+    pub bankroll: Pubkey,
+    pub owner: Pubkey,
+    pub shares: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AllocationUpdated {
+    pub bankroll: Pubkey,
+    pub game: Pubkey,
+    pub allocation: u64,
+    pub total_allocated: u64,
+}
+
+#[event]
+pub struct GamePaused {
+    pub game: Pubkey,
+}
+
+#[event]
+pub struct GameResumed {
+    pub game: Pubkey,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub game: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub game: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct GamingLimitsUpdated {
+    pub player: Pubkey,
+    pub limits: GamingLimits,
+    pub pending_limits: GamingLimits,
+    pub pending_limits_at: i64,
+}
+
+#[event]
+pub struct SelfExcluded {
+    pub player: Pubkey,
+    pub excluded_until: i64,
+}
+
+#[event]
+pub struct BalanceDeposited {
+    pub player: Pubkey,
+    pub bankroll: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct BalanceWithdrawn {
+    pub player: Pubkey,
+    pub bankroll: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct AutoBetOpened {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub choice: CoinChoice,
+    pub strategy: AutoBetStrategy,
+}
+
+#[event]
+pub struct AutoBetStepped {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub bet_amount: u64,
+    pub won: bool,
+    pub payout: u64,
+    pub bets_placed: u32,
+    pub vrf_proof: [u8; 32],
+}
+
+#[event]
+pub struct AutoBetEnded {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub reason: AutoBetStop,
+    pub bets_placed: u32,
+    pub wagered: u64,
+    pub won: u64,
+}
+
+#[event]
+pub struct SessionCreated {
+    pub player: Pubkey,
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub expires_at: i64,
+    pub wager_cap: u64,
+}
+
+#[event]
+pub struct SessionRevoked {
+    pub player: Pubkey,
+    pub signer: Pubkey,
+    pub wagered: u64,
+}
+
+#[event]
+pub struct BetRefunded {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub fee_amount: u64,
+}
+
+#[event]
+pub struct BetCancelled {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub fee_amount: u64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct EscrowMigrated {
+    pub game: Pubkey,
+    pub bankroll: Pubkey,
+    pub amount: u64,
+    pub allocation: u64,
+}
+
+#[event]
+pub struct RoleAssigned {
+    pub game: Pubkey,
+    pub role: GameRole,
+    pub holder: Pubkey,
+}
+
+#[event]
+pub struct CouncilCreated {
+    pub council: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct CouncilProposalCreated {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub game: Pubkey,
+    pub proposer: Pubkey,
+}
+
+#[event]
+pub struct CouncilProposalApproved {
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct CouncilProposalExecuted {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub game: Pubkey,
+}
+
+#[event]
+pub struct EmergencyStopSet {
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct ConfigProposed {
+    pub game: Pubkey,
+    pub config: GameConfig,
+    pub config_delay: i64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct ConfigApplied {
+    pub game: Pubkey,
+    pub config: GameConfig,
+    pub config_delay: i64,
+}
+
+#[event]
+pub struct MintAccepted {
+    pub game: Pubkey,
+    pub mint: Pubkey,
+    pub min_bet: u64,
+    pub max_bet: u64,
+}
+
+#[event]
+pub struct MintLimitsUpdated {
+    pub game: Pubkey,
+    pub mint: Pubkey,
+    pub min_bet: u64,
+    pub max_bet: u64,
+}
+
+#[event]
+pub struct SolVaultFunded {
+    pub game: Pubkey,
+    pub amount: u64,
+    pub allocation: u64,
+}
+
+#[event]
+pub struct SolVaultWithdrawn {
+    pub game: Pubkey,
+    pub amount: u64,
+    pub allocation: u64,
 }
 
 #[event]
@@ -195,6 +1068,12 @@ pub struct RoundStarted {
     pub round_number: u32,
 }
 
+#[event]
+pub struct RoundActive {
+    pub game: Pubkey,
+    pub round_number: u32,
+}
+
 #[event]
 pub struct RoundEnded {
     pub game: Pubkey,
@@ -227,3 +1106,47 @@ pub struct GachaPull {
     pub multiplier: u32,
     pub payout: u64,
 }
+
+#[event]
+pub struct Burned {
+    pub game: Pubkey,
+    pub amount: u64,
+    pub realized_profit: i64,
+    pub total_burned: u64,
+}
+
+#[event]
+pub struct ReferrerRegistered {
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralAccrued {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralClaimed {
+    pub referrer: Pubkey,
+    pub bankroll: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct VipTiersUpdated {
+    pub bankroll: Pubkey,
+    pub thresholds: [u64; VIP_TIERS],
+    pub rakeback_bps: [u16; VIP_TIERS],
+}
+
+#[event]
+pub struct RakebackClaimed {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub total_rakeback: u64,
+}
@@ -1,10 +1,11 @@
 //! Account structures and types for CC Casino
 
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 
 // ============ GAME TYPES ============
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
 pub enum GameType {
     CoinFlip,
     Crash,
@@ -12,27 +13,52 @@ pub enum GameType {
     Gacha,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum CoinChoice {
     Heads,
     Tails,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
 pub enum BetOutcome {
     Pending,
     Win,
     Lose,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
 pub enum RoundPhase {
     Betting,
     Active,
     Ended,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+/// Currency a game is denominated in
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Currency {
+    Token,  // SPL / Token-2022 mint via the shared bankroll
+    Native, // SOL via the game's lamport vault
+}
+
+/// Delegated game role assignable by the authority
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameRole {
+    Operator,  // Starts rounds and pauses the game
+    Resolver,  // Settles bets with randomness
+    Treasurer, // Funds and withdraws game money
+}
+
+/// Why an auto-bet session stopped placing bets
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AutoBetStop {
+    Completed,
+    StopLoss,
+    TakeProfit,
+    OutOfFunds,
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
 pub enum PrizeTier {
     Common,    // 74% - 0.5x
     Rare,      // 20% - 2x
@@ -40,9 +66,36 @@ pub enum PrizeTier {
     Legendary, // 1% - 10x
 }
 
+/// Privileged game action a council can execute once approved
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
+pub enum CouncilAction {
+    WithdrawFees { amount: u64, recipient: Pubkey },
+    ProposeConfig { config: GameConfig, config_delay: i64 },
+    ApplyConfig,
+    PauseGame,
+    ResumeGame,
+    ProposeAuthority { new_authority: Pubkey },
+    AcceptAuthority,
+    AssignRole { role: GameRole, holder: Pubkey },
+    SetBurnShare { burn_bps: u16 },
+    AddGameMint { min_bet: u64, max_bet: u64 },
+    SetMintLimits { min_bet: u64, max_bet: u64 },
+    SetResolutionTimeout { slots: u64 },
+    SetBetThrottle { bet_window_seconds: u32, max_bets_per_window: u16 },
+    SetCancelPolicy { cancel_window_slots: u64, cancel_fee_bps: u16 },
+    FundSolVault { amount: u64 },
+    WithdrawSolVault { amount: u64, recipient: Pubkey },
+    AdvanceCrashRound,
+}
+
+impl CouncilAction {
+    /// Borsh size of the largest variant (tag + WithdrawFees)
+    pub const MAX_LEN: usize = 1 + 8 + 32;
+}
+
 // ============ CONFIG ============
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
 pub struct GameConfig {
     /// Minimum bet amount in token lamports
     pub min_bet: u64,
@@ -60,85 +113,1235 @@ pub struct GameConfig {
     pub cooldown_seconds: u16,
 }
 
-impl Default for GameConfig {
-    fn default() -> Self {
-        Self {
-            min_bet: 1_000_000,          // 1 $CC
-            max_bet: 1_000_000_000,      // 1000 $CC
-            house_edge_bps: 200,         // 2%
-            platform_fee_lamports: 1_000_000, // 0.001 SOL
-            cooldown_seconds: 0,         // No cooldown
-        }
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            min_bet: 1_000_000,          // 1 $CC
+            max_bet: 1_000_000_000,      // 1000 $CC
+            house_edge_bps: 200,         // 2%
+            platform_fee_lamports: 1_000_000, // 0.001 SOL
+            cooldown_seconds: 0,         // No cooldown
+        }
+    }
+}
+
+// ============ ACCOUNTS ============
+
+/// Program-wide settings (singleton PDA)
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalConfig {
+    /// Account layout version
+    pub version: u8,
+
+    /// Admin allowed to change global settings (the program upgrade authority at init)
+    pub admin: Pubkey,
+
+    /// Emergency stop: blocks every bet instruction while set
+    pub paused: bool,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Reserved for future use
+    pub _reserved: [u8; 63],
+}
+
+impl GlobalConfig {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // admin
+        1 +   // paused
+        1 +   // bump
+        63;   // reserved
+}
+
+/// Allowlist entry permitting a wallet to create games
+#[account]
+#[derive(InitSpace)]
+pub struct GameCreator {
+    /// Account layout version
+    pub version: u8,
+
+    /// Allowed creator wallet
+    pub creator: Pubkey,
+
+    /// Admin that added the creator
+    pub added_by: Pubkey,
+
+    /// Added timestamp
+    pub added_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl GameCreator {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // creator
+        32 +  // added_by
+        8 +   // added_at
+        1;    // bump
+}
+
+/// Registry of every game created (entries are enumerable by index)
+#[account]
+#[derive(InitSpace)]
+pub struct GameRegistry {
+    /// Account layout version
+    pub version: u8,
+
+    /// Number of registered games (next entry index)
+    pub game_count: u64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl GameRegistry {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        8 +   // game_count
+        1;    // bump
+}
+
+/// One registered game
+#[account]
+#[derive(InitSpace)]
+pub struct RegistryEntry {
+    /// Account layout version
+    pub version: u8,
+
+    /// Entry index in the registry
+    pub index: u64,
+
+    /// Game state address
+    pub game: Pubkey,
+
+    /// Game slug
+    pub slug: [u8; 32],
+
+    /// Game type
+    pub game_type: GameType,
+
+    /// Wallet that created the game
+    pub creator: Pubkey,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl RegistryEntry {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        8 +   // index
+        32 +  // game
+        32 +  // slug
+        1 +   // game_type
+        32 +  // creator
+        8 +   // created_at
+        1;    // bump
+}
+
+/// Default resolution timeout for new games (~1 hour of 400ms slots)
+pub const DEFAULT_RESOLUTION_TIMEOUT_SLOTS: u64 = 9_000;
+
+/// Shortest resolution timeout a game may set (~1 minute)
+pub const MIN_RESOLUTION_TIMEOUT_SLOTS: u64 = 150;
+
+/// Main game state account
+#[account]
+#[derive(InitSpace)]
+pub struct GameState {
+    /// Account layout version
+    pub version: u8,
+
+    /// Game authority (brain wallet)
+    pub authority: Pubkey,
+
+    /// Game type
+    pub game_type: GameType,
+
+    /// URL slug for this game
+    pub slug: [u8; 32], // Fixed size for deterministic account size
+
+    /// Game configuration
+    pub config: GameConfig,
+
+    /// $CC token mint (selects the shared bankroll this game draws on;
+    /// default for native SOL games)
+    pub cc_mint: Pubkey,
+
+    /// Game state PDA bump
+    pub escrow_bump: u8,
+
+    /// Is game currently active?
+    pub is_active: bool,
+
+    /// Total volume in token lamports
+    pub total_volume: u64,
+
+    /// Total fees collected in SOL lamports
+    pub total_fees: u64,
+
+    /// Current round number (for crash/jackpot)
+    pub current_round: u32,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Share of realized house profit burned by the crank (basis points)
+    pub burn_bps: u16,
+
+    /// Total $CC burned from escrow in token lamports
+    pub total_burned: u64,
+
+    /// Net house profit from settled bets (stakes minus payouts)
+    pub realized_profit: i64,
+
+    /// Realized profit already accounted for by previous burns
+    pub burned_profit_mark: i64,
+
+    /// Share of the bankroll this game may put at risk, in token lamports
+    /// (native SOL games: lamports funded into their own vault)
+    pub allocation: u64,
+
+    /// Currency bets and payouts are made in
+    pub currency: Currency,
+
+    /// Native SOL vault PDA bump (unused for token games)
+    pub sol_vault_bump: u8,
+
+    /// Number of entries in `accepted_mints`
+    pub accepted_mint_count: u8,
+
+    /// Additional mints accepted besides `cc_mint` (each has a MintConfig)
+    pub accepted_mints: [Pubkey; MAX_ACCEPTED_MINTS],
+
+    /// Seconds a proposed config change waits before it can be applied
+    pub config_delay: i64,
+
+    /// Proposed new authority awaiting acceptance (default if none)
+    pub pending_authority: Pubkey,
+
+    /// Hot key that starts rounds and may pause the game
+    pub operator: Pubkey,
+
+    /// Key that settles bets with randomness
+    pub resolver: Pubkey,
+
+    /// Key that funds and withdraws game money
+    pub treasurer: Pubkey,
+
+    /// Slots after a bet or round starts before its players may reclaim
+    /// their stakes if it was never resolved
+    pub resolution_timeout_slots: u64,
+
+    /// Length of the window bets per player are counted in (seconds)
+    pub bet_window_seconds: u32,
+
+    /// Bets a player may place per window (0 = unlimited)
+    pub max_bets_per_window: u16,
+
+    /// Slots after a bet during which the player may cancel it and no
+    /// randomness may be revealed for it (0 = no cancellation)
+    pub cancel_window_slots: u64,
+
+    /// Share of the stake kept by the house on cancellation (basis points)
+    pub cancel_fee_bps: u16,
+
+    /// Reserved for future use
+    pub _reserved: [u8; 4],
+}
+
+impl GameState {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // authority
+        1 +   // game_type
+        32 +  // slug
+        (8 + 8 + 2 + 8 + 2) + // config
+        32 +  // cc_mint
+        1 +   // escrow_bump
+        1 +   // is_active
+        8 +   // total_volume
+        8 +   // total_fees
+        4 +   // current_round
+        8 +   // created_at
+        2 +   // burn_bps
+        8 +   // total_burned
+        8 +   // realized_profit
+        8 +   // burned_profit_mark
+        8 +   // allocation
+        1 +   // currency
+        1 +   // sol_vault_bump
+        1 +   // accepted_mint_count
+        32 * MAX_ACCEPTED_MINTS + // accepted_mints
+        8 +   // config_delay
+        32 +  // pending_authority
+        32 +  // operator
+        32 +  // resolver
+        32 +  // treasurer
+        8 +   // resolution_timeout_slots
+        4 +   // bet_window_seconds
+        2 +   // max_bets_per_window
+        8 +   // cancel_window_slots
+        2 +   // cancel_fee_bps
+        4;    // reserved
+
+    pub fn slug_as_str(&self) -> String {
+        String::from_utf8_lossy(&self.slug)
+            .trim_end_matches('\0')
+            .to_string()
+    }
+
+    /// Record a settled stake and payout against realized house profit
+    pub fn record_settlement(&mut self, stake: u64, payout: u64) {
+        self.realized_profit = self
+            .realized_profit
+            .saturating_add(stake as i64)
+            .saturating_sub(payout as i64);
+    }
+
+    /// Bankroll liquidity still available to this game: its allocation
+    /// grown or drained by the profit it has realized (net of burns)
+    pub fn available_liquidity(&self) -> u64 {
+        (self.allocation as i128 + self.realized_profit as i128 - self.total_burned as i128)
+            .max(0) as u64
+    }
+
+    /// Whether bets may be placed in `mint` (the primary mint or an accepted one)
+    pub fn accepts_mint(&self, mint: &Pubkey) -> bool {
+        *mint == self.cc_mint
+            || self.accepted_mints[..self.accepted_mint_count as usize].contains(mint)
+    }
+
+    /// Whether a bet in `mint` accrues profile stats, rakeback and referral
+    /// rewards, which are denominated in the primary bankroll token
+    pub fn tracks_rewards(&self, mint: &Pubkey) -> bool {
+        self.currency == Currency::Token && *mint == self.cc_mint
+    }
+
+    /// Min and max bet for the mint a bet is placed in
+    pub fn bet_limits(&self, mint_config: Option<&MintConfig>) -> (u64, u64) {
+        match mint_config {
+            Some(mint_config) => (mint_config.min_bet, mint_config.max_bet),
+            None => (self.config.min_bet, self.config.max_bet),
+        }
+    }
+
+    /// Liquidity available for the mint a bet is placed in
+    pub fn liquidity_for(&self, mint_config: Option<&MintConfig>) -> u64 {
+        match mint_config {
+            Some(mint_config) => mint_config.available_liquidity(),
+            None => self.available_liquidity(),
+        }
+    }
+
+    /// Record a settlement against the ledger of the mint the bet was placed in
+    pub fn settle(&mut self, mint_config: Option<&mut MintConfig>, stake: u64, payout: u64) {
+        match mint_config {
+            Some(mint_config) => mint_config.record_settlement(stake, payout),
+            None => self.record_settlement(stake, payout),
+        }
+    }
+
+    /// Add wagered volume to the ledger of the mint the bet was placed in
+    pub fn add_volume(&mut self, mint_config: Option<&mut MintConfig>, amount: u64) {
+        match mint_config {
+            Some(mint_config) => {
+                mint_config.total_volume = mint_config.total_volume.checked_add(amount).unwrap()
+            }
+            None => self.total_volume = self.total_volume.checked_add(amount).unwrap(),
+        }
+    }
+
+    /// Amount due to the burn crank from profit realized since the last burn
+    pub fn pending_burn(&self) -> u64 {
+        let unburned = self.realized_profit.saturating_sub(self.burned_profit_mark);
+        if unburned <= 0 {
+            return 0;
+        }
+        ((unburned as u128 * self.burn_bps as u128) / 10000) as u64
+    }
+
+    /// Whether a bet or round started at `since_slot` has gone unresolved
+    /// long enough for its players to reclaim their stakes
+    pub fn resolution_expired(&self, since_slot: u64, slot: u64) -> bool {
+        slot >= since_slot.saturating_add(self.resolution_timeout_slots)
+    }
+
+    /// Whether a bet placed at `bet_slot` can still be cancelled. Resolution
+    /// is refused for exactly as long, so a bet is never both cancellable and
+    /// revealed.
+    pub fn cancel_window_open(&self, bet_slot: u64, slot: u64) -> bool {
+        self.cancel_window_slots > 0 && slot <= bet_slot.saturating_add(self.cancel_window_slots)
+    }
+}
+
+/// Pending game config change (applicable once the game's delay has passed)
+#[account]
+#[derive(InitSpace)]
+pub struct ConfigProposal {
+    /// Account layout version
+    pub version: u8,
+
+    /// Game being reconfigured
+    pub game: Pubkey,
+
+    /// Proposed configuration
+    pub config: GameConfig,
+
+    /// Proposed delay for future config changes
+    pub config_delay: i64,
+
+    /// Proposal timestamp
+    pub proposed_at: i64,
+
+    /// Earliest apply timestamp
+    pub effective_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl ConfigProposal {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // game
+        (8 + 8 + 2 + 8 + 2) + // config
+        8 +   // config_delay
+        8 +   // proposed_at
+        8 +   // effective_at
+        1;    // bump
+}
+
+/// Maximum number of council members
+pub const MAX_COUNCIL_MEMBERS: usize = 10;
+
+/// M-of-N multisig that can own games (set as their authority)
+#[account]
+#[derive(InitSpace)]
+pub struct Council {
+    /// Account layout version
+    pub version: u8,
+
+    /// Key the council PDA is derived from
+    pub create_key: Pubkey,
+
+    /// Member wallets (first `member_count` entries are used)
+    pub members: [Pubkey; MAX_COUNCIL_MEMBERS],
+
+    /// Number of members
+    pub member_count: u8,
+
+    /// Approvals required to execute a proposal
+    pub threshold: u8,
+
+    /// Proposals created so far (next proposal index)
+    pub proposal_count: u64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl Council {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // create_key
+        32 * MAX_COUNCIL_MEMBERS + // members
+        1 +   // member_count
+        1 +   // threshold
+        8 +   // proposal_count
+        1;    // bump
+
+    /// Index of `member` in the member list
+    pub fn member_index(&self, member: &Pubkey) -> Option<usize> {
+        self.members[..self.member_count as usize]
+            .iter()
+            .position(|m| m == member)
+    }
+}
+
+/// Council proposal for one privileged action on one game
+#[account]
+#[derive(InitSpace)]
+pub struct CouncilProposal {
+    /// Account layout version
+    pub version: u8,
+
+    /// Council the proposal belongs to
+    pub council: Pubkey,
+
+    /// Proposal index within the council
+    pub index: u64,
+
+    /// Game the action targets
+    pub game: Pubkey,
+
+    /// Action to execute
+    pub action: CouncilAction,
+
+    /// Member that created the proposal
+    pub proposer: Pubkey,
+
+    /// Bitmap of approving member indices
+    pub approvals: u16,
+
+    /// Has the action been executed?
+    pub executed: bool,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl CouncilProposal {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // council
+        8 +   // index
+        32 +  // game
+        CouncilAction::MAX_LEN + // action
+        32 +  // proposer
+        2 +   // approvals
+        1 +   // executed
+        8 +   // created_at
+        1;    // bump
+
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.count_ones() as u8
+    }
+}
+
+/// Maximum number of additional mints a game can accept
+pub const MAX_ACCEPTED_MINTS: usize = 4;
+
+/// Limits and accounting for an additional mint accepted by a game
+#[account]
+#[derive(InitSpace)]
+pub struct MintConfig {
+    /// Account layout version
+    pub version: u8,
+
+    /// Game accepting the mint
+    pub game: Pubkey,
+
+    /// Accepted token mint (its bankroll vault holds the escrow)
+    pub mint: Pubkey,
+
+    /// Minimum bet in this mint's token lamports
+    pub min_bet: u64,
+
+    /// Maximum bet in this mint's token lamports
+    pub max_bet: u64,
+
+    /// Share of the mint's bankroll the game may put at risk
+    pub allocation: u64,
+
+    /// Net house profit from settled bets in this mint
+    pub realized_profit: i64,
+
+    /// Total volume in this mint's token lamports
+    pub total_volume: u64,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl MintConfig {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // game
+        32 +  // mint
+        8 +   // min_bet
+        8 +   // max_bet
+        8 +   // allocation
+        8 +   // realized_profit
+        8 +   // total_volume
+        8 +   // created_at
+        1;    // bump
+
+    /// Record a settled stake and payout against realized house profit
+    pub fn record_settlement(&mut self, stake: u64, payout: u64) {
+        self.realized_profit = self
+            .realized_profit
+            .saturating_add(stake as i64)
+            .saturating_sub(payout as i64);
+    }
+
+    /// Bankroll liquidity still available to the game in this mint
+    pub fn available_liquidity(&self) -> u64 {
+        (self.allocation as i128 + self.realized_profit as i128).max(0) as u64
+    }
+}
+
+/// Shared casino bankroll holding liquidity for every game on a mint
+#[account]
+#[derive(InitSpace)]
+pub struct Bankroll {
+    /// Account layout version
+    pub version: u8,
+
+    /// Bankroll authority (funds, withdraws and rebalances allocations)
+    pub authority: Pubkey,
+
+    /// Token mint held by the vault
+    pub mint: Pubkey,
+
+    /// Sum of all per-game allocations in token lamports
+    pub total_allocated: u64,
+
+    /// Number of games drawing on this bankroll
+    pub game_count: u32,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Share token mint (pro-rata claim on the vault, mint authority = bankroll)
+    pub share_mint: Pubkey,
+
+    /// Seconds a withdrawal request waits before it can be executed
+    pub withdrawal_delay: i64,
+
+    /// Shares locked in pending withdrawal requests
+    pub pending_withdrawal_shares: u64,
+
+    /// Share of the house edge on every bet credited to the player's referrer (basis points)
+    pub referral_bps: u16,
+
+    /// Player balances held in the vault (not bankroll liquidity)
+    pub player_balances: u64,
+
+    /// Referral and rakeback rewards accrued but not yet claimed
+    pub unclaimed_rewards: u64,
+
+    /// Reserved for future use
+    pub _reserved: [u8; 5],
+}
+
+impl Bankroll {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // authority
+        32 +  // mint
+        8 +   // total_allocated
+        4 +   // game_count
+        8 +   // created_at
+        1 +   // bump
+        32 +  // share_mint
+        8 +   // withdrawal_delay
+        8 +   // pending_withdrawal_shares
+        2 +   // referral_bps
+        8 +   // player_balances
+        8 +   // unclaimed_rewards
+        5;    // reserved
+
+    /// Vault tokens backing liquidity shares and game allocations, i.e.
+    /// everything except player balances and rewards owed but not yet claimed
+    pub fn liquidity(&self, vault_balance: u64) -> u64 {
+        vault_balance
+            .saturating_sub(self.player_balances)
+            .saturating_sub(self.unclaimed_rewards)
+    }
+
+    /// Shares minted for a deposit, priced against the vault before the deposit.
+    /// Liquidity without shares must be seeded first, or the first depositor
+    /// would own it.
+    pub fn shares_for_deposit(amount: u64, vault_balance: u64, share_supply: u64) -> Option<u64> {
+        if share_supply == 0 {
+            return (vault_balance == 0).then_some(amount);
+        }
+        if vault_balance == 0 {
+            return None;
+        }
+        u64::try_from(amount as u128 * share_supply as u128 / vault_balance as u128).ok()
+    }
+
+    /// Tokens owed for redeeming shares at the current vault balance
+    pub fn amount_for_shares(shares: u64, vault_balance: u64, share_supply: u64) -> u64 {
+        if share_supply == 0 {
+            return 0;
+        }
+        (shares as u128 * vault_balance as u128 / share_supply as u128) as u64
+    }
+}
+
+/// Queued liquidity withdrawal (executable once the bankroll delay has passed)
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalRequest {
+    /// Account layout version
+    pub version: u8,
+
+    /// Liquidity provider
+    pub owner: Pubkey,
+
+    /// Bankroll being withdrawn from
+    pub bankroll: Pubkey,
+
+    /// Shares locked for redemption
+    pub shares: u64,
+
+    /// Request timestamp
+    pub requested_at: i64,
+
+    /// Earliest execution timestamp
+    pub available_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl WithdrawalRequest {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // owner
+        32 +  // bankroll
+        8 +   // shares
+        8 +   // requested_at
+        8 +   // available_at
+        1;    // bump
+}
+
+/// Per-player profile shared by every game
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    /// Account layout version
+    pub version: u8,
+
+    /// Player wallet
+    pub player: Pubkey,
+
+    /// Referrer wallet (default pubkey if none registered)
+    pub referrer: Pubkey,
+
+    /// Lifetime amount wagered in token lamports
+    pub total_wagered: u64,
+
+    /// Lifetime payouts received
+    pub total_won: u64,
+
+    /// Lifetime stakes lost (stake minus payout on losing bets)
+    pub total_lost: u64,
+
+    /// Number of bets placed
+    pub bet_count: u64,
+
+    /// Largest single payout
+    pub biggest_win: u64,
+
+    /// Timestamp of the most recent bet
+    pub last_bet_at: i64,
+
+    /// Rakeback accrued but not yet claimed, in token lamports
+    pub rakeback_claimable: u64,
+
+    /// Lifetime rakeback accrued
+    pub total_rakeback: u64,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Bets are refused until this timestamp (self-exclusion)
+    pub excluded_until: i64,
+
+    /// Wager and loss limits in force
+    pub limits: GamingLimits,
+
+    /// Limits that loosen the current ones, waiting out the cool-off
+    pub pending_limits: GamingLimits,
+
+    /// When `pending_limits` take effect (0 if none pending)
+    pub pending_limits_at: i64,
+
+    /// Start of the current daily limit period
+    pub day_start: i64,
+
+    /// Wagered in the current day
+    pub day_wagered: u64,
+
+    /// Won in the current day
+    pub day_won: u64,
+
+    /// Start of the current weekly limit period
+    pub week_start: i64,
+
+    /// Wagered in the current week
+    pub week_wagered: u64,
+
+    /// Won in the current week
+    pub week_won: u64,
+
+    /// Reserved for future use
+    pub _reserved: [u8; 63],
+}
+
+impl PlayerProfile {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // player
+        32 +  // referrer
+        8 +   // total_wagered
+        8 +   // total_won
+        8 +   // total_lost
+        8 +   // bet_count
+        8 +   // biggest_win
+        8 +   // last_bet_at
+        8 +   // rakeback_claimable
+        8 +   // total_rakeback
+        8 +   // created_at
+        1 +   // bump
+        8 +   // excluded_until
+        (8 * 4) + // limits
+        (8 * 4) + // pending_limits
+        8 +   // pending_limits_at
+        8 +   // day_start
+        8 +   // day_wagered
+        8 +   // day_won
+        8 +   // week_start
+        8 +   // week_wagered
+        8 +   // week_won
+        63;   // reserved
+
+    /// Fill in a freshly created profile (no-op once initialized)
+    pub fn ensure_initialized(&mut self, player: Pubkey, bump: u8, now: i64) {
+        if self.created_at == 0 {
+            self.version = Self::VERSION;
+            self.player = player;
+            self.created_at = now;
+            self.bump = bump;
+        }
+    }
+
+    pub fn has_referrer(&self) -> bool {
+        self.referrer != Pubkey::default()
+    }
+
+    /// Record a settled bet's stake and payout
+    pub fn record_result(&mut self, stake: u64, payout: u64) {
+        self.total_won = self.total_won.saturating_add(payout);
+        self.day_won = self.day_won.saturating_add(payout);
+        self.week_won = self.week_won.saturating_add(payout);
+        if payout < stake {
+            self.total_lost = self.total_lost.saturating_add(stake - payout);
+        }
+        self.biggest_win = self.biggest_win.max(payout);
+    }
+}
+
+/// Number of VIP tiers
+pub const VIP_TIERS: usize = 4;
+
+/// Cool-off before loosened gaming limits take effect (24 hours)
+pub const LIMIT_RAISE_DELAY_SECONDS: i64 = 86_400;
+
+/// Self-imposed responsible-gaming limits in primary-token lamports (0 = none)
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GamingLimits {
+    pub daily_wager: u64,
+    pub weekly_wager: u64,
+    pub daily_loss: u64,
+    pub weekly_loss: u64,
+}
+
+impl GamingLimits {
+    /// Field-wise stricter of `self` and `other`, treating 0 as no limit
+    pub fn strictest(&self, other: &Self) -> Self {
+        let min = |a: u64, b: u64| match (a, b) {
+            (0, b) => b,
+            (a, 0) => a,
+            (a, b) => a.min(b),
+        };
+        Self {
+            daily_wager: min(self.daily_wager, other.daily_wager),
+            weekly_wager: min(self.weekly_wager, other.weekly_wager),
+            daily_loss: min(self.daily_loss, other.daily_loss),
+            weekly_loss: min(self.weekly_loss, other.weekly_loss),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Per-player, per-game bet throttle state
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerThrottle {
+    /// Account layout version
+    pub version: u8,
+
+    /// Player wallet
+    pub player: Pubkey,
+
+    /// Game being throttled
+    pub game: Pubkey,
+
+    /// Timestamp of the player's last bet in this game
+    pub last_bet_at: i64,
+
+    /// Start of the current bet-counting window
+    pub window_start: i64,
+
+    /// Bets placed in the current window
+    pub window_bets: u16,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl PlayerThrottle {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // player
+        32 +  // game
+        8 +   // last_bet_at
+        8 +   // window_start
+        2 +   // window_bets
+        1;    // bump
+}
+
+/// Player funds deposited into a bankroll vault, bet and paid out without
+/// a token transfer per bet
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerBalance {
+    /// Account layout version
+    pub version: u8,
+
+    /// Player wallet
+    pub player: Pubkey,
+
+    /// Bankroll whose vault holds the funds
+    pub bankroll: Pubkey,
+
+    /// Tokens available to bet or withdraw
+    pub amount: u64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl PlayerBalance {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // player
+        32 +  // bankroll
+        8 +   // amount
+        1;    // bump
+}
+
+/// Rules an auto-bet session follows. Bet adjustments scale the previous
+/// bet in basis points (20000 doubles it, as in a martingale); 0 resets to
+/// the base bet. A stop-loss or take-profit of 0 is disabled.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AutoBetStrategy {
+    pub base_bet: u64,
+    pub bet_count: u32,
+    pub on_win_bps: u32,
+    pub on_loss_bps: u32,
+    pub stop_loss: u64,
+    pub take_profit: u64,
+}
+
+impl AutoBetStrategy {
+    /// Bet to place after a result, given the bet just settled
+    pub fn next_bet(&self, last_bet: u64, won: bool) -> u64 {
+        let bps = if won { self.on_win_bps } else { self.on_loss_bps };
+        if bps == 0 {
+            return self.base_bet;
+        }
+        u64::try_from(last_bet as u128 * bps as u128 / 10000).unwrap_or(u64::MAX)
+    }
+}
+
+/// Coin flip auto-bet session, executed step by step by the game resolver
+/// from the player's internal balance
+#[account]
+#[derive(InitSpace)]
+pub struct AutoBet {
+    /// Account layout version
+    pub version: u8,
+
+    /// Player wallet
+    pub player: Pubkey,
+
+    /// Game the session bets on
+    pub game: Pubkey,
+
+    /// Bankroll holding the player's balance
+    pub bankroll: Pubkey,
+
+    /// Side bet on every flip
+    pub choice: CoinChoice,
+
+    /// Rules the resolver must follow
+    pub strategy: AutoBetStrategy,
+
+    /// Stake of the next bet
+    pub current_bet: u64,
+
+    /// Bets placed so far
+    pub bets_placed: u32,
+
+    /// Total staked so far
+    pub wagered: u64,
+
+    /// Total paid out so far
+    pub won: u64,
+
+    /// Still placing bets?
+    pub active: bool,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl AutoBet {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // player
+        32 +  // game
+        32 +  // bankroll
+        1 +   // choice
+        36 +  // strategy
+        8 +   // current_bet
+        4 +   // bets_placed
+        8 +   // wagered
+        8 +   // won
+        1 +   // active
+        8 +   // created_at
+        1;    // bump
+
+    /// Reason the session must stop before its next bet, if any
+    pub fn stop_reason(&self) -> Option<AutoBetStop> {
+        let strategy = &self.strategy;
+        let loss = self.wagered.saturating_sub(self.won);
+        let profit = self.won.saturating_sub(self.wagered);
+        if self.bets_placed >= strategy.bet_count {
+            Some(AutoBetStop::Completed)
+        } else if strategy.take_profit > 0 && profit >= strategy.take_profit {
+            Some(AutoBetStop::TakeProfit)
+        } else if strategy.stop_loss > 0
+            && loss.saturating_add(self.current_bet) > strategy.stop_loss
+        {
+            // The next bet could push the loss past the stop-loss
+            Some(AutoBetStop::StopLoss)
+        } else {
+            None
+        }
+    }
+}
+
+/// Maximum number of games a session key can be scoped to
+pub const MAX_SESSION_GAMES: usize = 4;
+
+/// Longest a session key can stay valid
+pub const MAX_SESSION_DURATION_SECONDS: i64 = 7 * 86_400;
+
+/// Ephemeral key a player authorized to bet on their behalf
+#[account]
+#[derive(InitSpace)]
+pub struct Session {
+    /// Account layout version
+    pub version: u8,
+
+    /// Player wallet the session bets for (receives all payouts)
+    pub player: Pubkey,
+
+    /// Ephemeral key allowed to sign bets
+    pub signer: Pubkey,
+
+    /// Mint the wager cap is counted in (default for native SOL)
+    pub mint: Pubkey,
+
+    /// Timestamp after which the session key is refused
+    pub expires_at: i64,
+
+    /// Total stake the session key may place
+    pub wager_cap: u64,
+
+    /// Stake placed through the session so far
+    pub wagered: u64,
+
+    /// Number of entries used in `games`
+    pub game_count: u8,
+
+    /// Games the session key may bet on
+    pub games: [Pubkey; MAX_SESSION_GAMES],
+
+    /// Creation timestamp
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl Session {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // player
+        32 +  // signer
+        32 +  // mint
+        8 +   // expires_at
+        8 +   // wager_cap
+        8 +   // wagered
+        1 +   // game_count
+        32 * MAX_SESSION_GAMES + // games
+        8 +   // created_at
+        1;    // bump
+
+    /// Whether the session key may bet on `game`
+    pub fn covers(&self, game: &Pubkey) -> bool {
+        self.games[..self.game_count as usize].contains(game)
     }
 }
 
-// ============ ACCOUNTS ============
-
-/// Main game state account
+/// VIP volume tiers and rakeback rates for a bankroll
 #[account]
-pub struct GameState {
-    /// Game authority (brain wallet)
-    pub authority: Pubkey,
+#[derive(InitSpace)]
+pub struct VipConfig {
+    /// Account layout version
+    pub version: u8,
 
-    /// Game type
-    pub game_type: GameType,
+    /// Bankroll the rakeback is paid from
+    pub bankroll: Pubkey,
 
-    /// URL slug for this game
-    pub slug: [u8; 32], // Fixed size for deterministic account size
+    /// Lifetime wager needed to reach each tier, ascending
+    pub thresholds: [u64; VIP_TIERS],
 
-    /// Game configuration
-    pub config: GameConfig,
+    /// Share of house edge returned at each tier (basis points)
+    pub rakeback_bps: [u16; VIP_TIERS],
 
-    /// $CC token mint
-    pub cc_mint: Pubkey,
+    /// Bump for PDA
+    pub bump: u8,
+}
 
-    /// Escrow token account PDA bump
-    pub escrow_bump: u8,
+impl VipConfig {
+    pub const VERSION: u8 = 1;
 
-    /// Is game currently active?
-    pub is_active: bool,
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // bankroll
+        8 * VIP_TIERS +  // thresholds
+        2 * VIP_TIERS +  // rakeback_bps
+        1;    // bump
 
-    /// Total volume in token lamports
-    pub total_volume: u64,
+    /// Rakeback rate for a player's lifetime wager volume
+    pub fn rakeback_bps_for(&self, total_wagered: u64) -> u16 {
+        self.thresholds
+            .iter()
+            .zip(self.rakeback_bps.iter())
+            .rev()
+            .find(|(threshold, _)| total_wagered >= **threshold)
+            .map(|(_, bps)| *bps)
+            .unwrap_or(0)
+    }
+}
 
-    /// Total fees collected in SOL lamports
-    pub total_fees: u64,
+/// Referral earnings for one referrer on one bankroll
+#[account]
+#[derive(InitSpace)]
+pub struct ReferrerAccount {
+    /// Account layout version
+    pub version: u8,
 
-    /// Current round number (for crash/jackpot)
-    pub current_round: u32,
+    /// Referrer wallet
+    pub referrer: Pubkey,
 
-    /// Created timestamp
-    pub created_at: i64,
+    /// Bankroll the rewards are paid from
+    pub bankroll: Pubkey,
 
-    /// Reserved for future use
-    pub _reserved: [u8; 64],
+    /// Rewards accrued but not yet claimed, in token lamports
+    pub claimable: u64,
+
+    /// Lifetime rewards accrued
+    pub total_earned: u64,
+
+    /// Lifetime rewards claimed
+    pub total_claimed: u64,
+
+    /// Players registered under this referrer
+    pub referred_players: u32,
+
+    /// Bump for PDA
+    pub bump: u8,
 }
 
-impl GameState {
-    pub const LEN: usize = 8 +  // discriminator
-        32 +  // authority
-        1 +   // game_type
-        32 +  // slug
-        (8 + 8 + 2 + 8 + 2) + // config
-        32 +  // cc_mint
-        1 +   // escrow_bump
-        1 +   // is_active
-        8 +   // total_volume
-        8 +   // total_fees
-        4 +   // current_round
-        8 +   // created_at
-        64;   // reserved
+impl ReferrerAccount {
+    pub const VERSION: u8 = 1;
 
-    pub fn slug_as_str(&self) -> String {
-        String::from_utf8_lossy(&self.slug)
-            .trim_end_matches('\0')
-            .to_string()
-    }
+    pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
+        32 +  // referrer
+        32 +  // bankroll
+        8 +   // claimable
+        8 +   // total_earned
+        8 +   // total_claimed
+        4 +   // referred_players
+        1;    // bump
 }
 
 /// Individual bet/play record
 #[account]
+#[derive(InitSpace)]
 pub struct PlayerBet {
+    /// Account layout version
+    pub version: u8,
+
     /// Player wallet
     pub player: Pubkey,
 
@@ -148,6 +1351,9 @@ pub struct PlayerBet {
     /// Round number (for crash/jackpot, 0 for instant games)
     pub round_number: u32,
 
+    /// Mint the bet was placed in (default for native SOL games)
+    pub mint: Pubkey,
+
     /// Bet amount in token lamports
     pub bet_amount: u64,
 
@@ -169,18 +1375,34 @@ pub struct PlayerBet {
     /// Timestamp of bet
     pub bet_at: i64,
 
+    /// Slot the bet was placed in (starts the resolution timeout)
+    pub bet_slot: u64,
+
     /// Timestamp of resolution (0 if pending)
     pub resolved_at: i64,
 
+    /// Stake came from the player's internal balance (payouts go back there)
+    pub from_balance: bool,
+
+    /// Wallet that paid the account's rent and the platform fee (gets both back)
+    pub rent_payer: Pubkey,
+
+    /// Payout multiplier in force when the bet was placed (basis points)
+    pub multiplier_bps: u32,
+
     /// Bump for PDA
     pub bump: u8,
 }
 
 impl PlayerBet {
+    pub const VERSION: u8 = 1;
+
     pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
         32 +  // player
         32 +  // game
         4 +   // round_number
+        32 +  // mint
         8 +   // bet_amount
         8 +   // fee_amount
         1 +   // bet_choice
@@ -188,13 +1410,21 @@ impl PlayerBet {
         8 +   // payout_amount
         32 +  // vrf_result
         8 +   // bet_at
+        8 +   // bet_slot
         8 +   // resolved_at
+        1 +   // from_balance
+        32 +  // rent_payer
+        4 +   // multiplier_bps
         1;    // bump
 }
 
 /// Round state for multi-player games (crash/jackpot)
 #[account]
+#[derive(InitSpace)]
 pub struct RoundState {
+    /// Account layout version
+    pub version: u8,
+
     /// Game this round belongs to
     pub game: Pubkey,
 
@@ -204,7 +1434,7 @@ pub struct RoundState {
     /// Current phase
     pub phase: RoundPhase,
 
-    /// Total pool size in token lamports
+    /// Total pool size in the game's primary currency
     pub pool_size: u64,
 
     /// Number of participants
@@ -219,18 +1449,27 @@ pub struct RoundState {
     /// Round start timestamp
     pub started_at: i64,
 
+    /// Slot the round started in (starts the resolution timeout)
+    pub started_slot: u64,
+
     /// Betting end timestamp
     pub betting_ends_at: i64,
 
     /// Round end timestamp
     pub ended_at: i64,
 
+    /// Wallet that paid the round's rent (default for legacy rounds)
+    pub rent_payer: Pubkey,
+
     /// Bump for PDA
     pub bump: u8,
 }
 
 impl RoundState {
+    pub const VERSION: u8 = 1;
+
     pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
         32 +  // game
         4 +   // round_number
         1 +   // phase
@@ -239,23 +1478,35 @@ impl RoundState {
         32 +  // vrf_result
         32 +  // result
         8 +   // started_at
+        8 +   // started_slot
         8 +   // betting_ends_at
         8 +   // ended_at
+        32 +  // rent_payer
         1;    // bump
 }
 
 /// Player state within a round (for crash tracking)
 #[account]
+#[derive(InitSpace)]
 pub struct RoundParticipant {
+    /// Account layout version
+    pub version: u8,
+
     /// Player wallet
     pub player: Pubkey,
 
     /// Round this participation is for
     pub round: Pubkey,
 
+    /// Mint the bet was placed in (default for native SOL games)
+    pub mint: Pubkey,
+
     /// Bet amount
     pub bet_amount: u64,
 
+    /// Platform fee paid in SOL lamports
+    pub fee_amount: u64,
+
     /// Has cashed out?
     pub cashed_out: bool,
 
@@ -271,38 +1522,64 @@ pub struct RoundParticipant {
     /// Cashout timestamp
     pub cashed_out_at: i64,
 
+    /// Stake came from the player's internal balance (refunds go back there)
+    pub from_balance: bool,
+
+    /// Wallet that paid the account's rent (gets it back on close)
+    pub rent_payer: Pubkey,
+
     /// Bump for PDA
     pub bump: u8,
 }
 
 impl RoundParticipant {
+    pub const VERSION: u8 = 1;
+
     pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
         32 +  // player
         32 +  // round
+        32 +  // mint
         8 +   // bet_amount
+        8 +   // fee_amount
         1 +   // cashed_out
         4 +   // cashout_multiplier
         8 +   // payout
         8 +   // joined_at
         8 +   // cashed_out_at
+        1 +   // from_balance
+        32 +  // rent_payer
         1;    // bump
 }
 
 /// Gacha pull result
 #[account]
+#[derive(InitSpace)]
 pub struct GachaPullResult {
+    /// Account layout version
+    pub version: u8,
+
     /// Player wallet
     pub player: Pubkey,
 
     /// Game this pull is for
     pub game: Pubkey,
 
+    /// Mint the pulls were paid in (default for native SOL games)
+    pub mint: Pubkey,
+
     /// Number of pulls
     pub pull_count: u8,
 
     /// Prize tiers won (up to 10 pulls)
     pub tiers: [u8; 10],
 
+    /// Stake received by the vault (after any transfer fee)
+    pub total_cost: u64,
+
+    /// Platform fee paid in SOL lamports
+    pub fee_amount: u64,
+
     /// Total payout
     pub total_payout: u64,
 
@@ -315,23 +1592,356 @@ pub struct GachaPullResult {
     /// Pull timestamp
     pub pulled_at: i64,
 
+    /// Slot the pull was made in (starts the resolution timeout)
+    pub pulled_slot: u64,
+
+    /// Timestamp of resolution (0 if pending)
+    pub resolved_at: i64,
+
+    /// Cost came from the player's internal balance (payouts go back there)
+    pub from_balance: bool,
+
+    /// Wallet that paid the account's rent (gets it back on close)
+    pub rent_payer: Pubkey,
+
+    /// Price of one pull when the pulls were made (prizes are multiples of it)
+    pub cost_per_pull: u64,
+
     /// Bump for PDA
     pub bump: u8,
 }
 
 impl GachaPullResult {
+    pub const VERSION: u8 = 1;
+
     pub const LEN: usize = 8 +  // discriminator
+        1 +   // version
         32 +  // player
         32 +  // game
+        32 +  // mint
         1 +   // pull_count
         10 +  // tiers
+        8 +   // total_cost
+        8 +   // fee_amount
         8 +   // total_payout
         32 +  // vrf_result
         1 +   // resolved
         8 +   // pulled_at
+        8 +   // pulled_slot
+        8 +   // resolved_at
+        1 +   // from_balance
+        32 +  // rent_payer
+        8 +   // cost_per_pull
         1;    // bump
 }
 
+// Hand-maintained LEN constants must match the derived layout size
+const _: () = {
+    assert!(CouncilAction::MAX_LEN == CouncilAction::INIT_SPACE);
+    assert!(GlobalConfig::LEN == 8 + GlobalConfig::INIT_SPACE);
+    assert!(GameCreator::LEN == 8 + GameCreator::INIT_SPACE);
+    assert!(GameRegistry::LEN == 8 + GameRegistry::INIT_SPACE);
+    assert!(RegistryEntry::LEN == 8 + RegistryEntry::INIT_SPACE);
+    assert!(GameState::LEN == 8 + GameState::INIT_SPACE);
+    assert!(ConfigProposal::LEN == 8 + ConfigProposal::INIT_SPACE);
+    assert!(Council::LEN == 8 + Council::INIT_SPACE);
+    assert!(CouncilProposal::LEN == 8 + CouncilProposal::INIT_SPACE);
+    assert!(MintConfig::LEN == 8 + MintConfig::INIT_SPACE);
+    assert!(Bankroll::LEN == 8 + Bankroll::INIT_SPACE);
+    assert!(WithdrawalRequest::LEN == 8 + WithdrawalRequest::INIT_SPACE);
+    assert!(PlayerProfile::LEN == 8 + PlayerProfile::INIT_SPACE);
+    assert!(PlayerThrottle::LEN == 8 + PlayerThrottle::INIT_SPACE);
+    assert!(PlayerBalance::LEN == 8 + PlayerBalance::INIT_SPACE);
+    assert!(Session::LEN == 8 + Session::INIT_SPACE);
+    assert!(AutoBet::LEN == 8 + AutoBet::INIT_SPACE);
+    assert!(VipConfig::LEN == 8 + VipConfig::INIT_SPACE);
+    assert!(ReferrerAccount::LEN == 8 + ReferrerAccount::INIT_SPACE);
+    assert!(PlayerBet::LEN == 8 + PlayerBet::INIT_SPACE);
+    assert!(RoundState::LEN == 8 + RoundState::INIT_SPACE);
+    assert!(RoundParticipant::LEN == 8 + RoundParticipant::INIT_SPACE);
+    assert!(GachaPullResult::LEN == 8 + GachaPullResult::INIT_SPACE);
+};
+
+// ============ LEGACY LAYOUTS ============
+//
+// Layouts deployed before accounts carried a version byte (version 0). They
+// are told apart from current accounts by their size and upgraded in memory
+// by `load_versioned`; `migrate_*` instructions persist the upgrade.
+
+/// GameState before versioning (per-game escrow, no bankroll or roles)
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GameStateV0 {
+    pub authority: Pubkey,
+    pub game_type: GameType,
+    pub slug: [u8; 32],
+    pub config: GameConfig,
+    pub cc_mint: Pubkey,
+    pub escrow_bump: u8,
+    pub is_active: bool,
+    pub total_volume: u64,
+    pub total_fees: u64,
+    pub current_round: u32,
+    pub created_at: i64,
+    pub _reserved: [u8; 64],
+}
+
+impl GameStateV0 {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +  // authority
+        1 +   // game_type
+        32 +  // slug
+        (8 + 8 + 2 + 8 + 2) + // config
+        32 +  // cc_mint
+        1 +   // escrow_bump
+        1 +   // is_active
+        8 +   // total_volume
+        8 +   // total_fees
+        4 +   // current_round
+        8 +   // created_at
+        64;   // reserved
+
+    /// Current layout with every later field at its neutral default; the
+    /// game starts with no bankroll allocation and all roles on the authority
+    pub fn upgrade(self) -> GameState {
+        GameState {
+            version: GameState::VERSION,
+            authority: self.authority,
+            game_type: self.game_type,
+            slug: self.slug,
+            config: self.config,
+            cc_mint: self.cc_mint,
+            escrow_bump: self.escrow_bump,
+            is_active: self.is_active,
+            total_volume: self.total_volume,
+            total_fees: self.total_fees,
+            current_round: self.current_round,
+            created_at: self.created_at,
+            burn_bps: 0,
+            total_burned: 0,
+            realized_profit: 0,
+            burned_profit_mark: 0,
+            allocation: 0,
+            currency: Currency::Token,
+            sol_vault_bump: 0,
+            accepted_mint_count: 0,
+            accepted_mints: [Pubkey::default(); MAX_ACCEPTED_MINTS],
+            config_delay: 0,
+            pending_authority: Pubkey::default(),
+            operator: self.authority,
+            resolver: self.authority,
+            treasurer: self.authority,
+            resolution_timeout_slots: DEFAULT_RESOLUTION_TIMEOUT_SLOTS,
+            bet_window_seconds: 0,
+            max_bets_per_window: 0,
+            cancel_window_slots: 0,
+            cancel_fee_bps: 0,
+            _reserved: [0; 4],
+        }
+    }
+}
+
+/// RoundState before versioning
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RoundStateV0 {
+    pub game: Pubkey,
+    pub round_number: u32,
+    pub phase: RoundPhase,
+    pub pool_size: u64,
+    pub participant_count: u32,
+    pub vrf_result: [u8; 32],
+    pub result: [u8; 32],
+    pub started_at: i64,
+    pub betting_ends_at: i64,
+    pub ended_at: i64,
+    pub bump: u8,
+}
+
+impl RoundStateV0 {
+    pub const LEN: usize = RoundState::LEN - 1 - 8 - 32; // no version, started_slot or rent_payer
+
+    /// The start slot is unknown, so the resolution timeout counts from `slot`
+    pub fn upgrade(self, slot: u64) -> RoundState {
+        RoundState {
+            version: RoundState::VERSION,
+            game: self.game,
+            round_number: self.round_number,
+            phase: self.phase,
+            pool_size: self.pool_size,
+            participant_count: self.participant_count,
+            vrf_result: self.vrf_result,
+            result: self.result,
+            started_at: self.started_at,
+            started_slot: slot,
+            betting_ends_at: self.betting_ends_at,
+            ended_at: self.ended_at,
+            rent_payer: Pubkey::default(),
+            bump: self.bump,
+        }
+    }
+}
+
+/// RoundParticipant before versioning (bets were always in the primary mint)
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RoundParticipantV0 {
+    pub player: Pubkey,
+    pub round: Pubkey,
+    pub bet_amount: u64,
+    pub cashed_out: bool,
+    pub cashout_multiplier: u32,
+    pub payout: u64,
+    pub joined_at: i64,
+    pub cashed_out_at: i64,
+    pub bump: u8,
+}
+
+impl RoundParticipantV0 {
+    // no version, mint, fee_amount, from_balance or rent_payer
+    pub const LEN: usize = RoundParticipant::LEN - 1 - 32 - 8 - 1 - 32;
+
+    /// Legacy entries were always paid for by the player from their wallet,
+    /// and their platform fee was never recorded
+    pub fn upgrade(self, mint: Pubkey) -> RoundParticipant {
+        RoundParticipant {
+            version: RoundParticipant::VERSION,
+            player: self.player,
+            round: self.round,
+            mint,
+            bet_amount: self.bet_amount,
+            fee_amount: 0,
+            cashed_out: self.cashed_out,
+            cashout_multiplier: self.cashout_multiplier,
+            payout: self.payout,
+            joined_at: self.joined_at,
+            cashed_out_at: self.cashed_out_at,
+            from_balance: false,
+            rent_payer: self.player,
+            bump: self.bump,
+        }
+    }
+}
+
+/// PlayerBet before versioning (bets were always in the primary mint)
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PlayerBetV0 {
+    pub player: Pubkey,
+    pub game: Pubkey,
+    pub round_number: u32,
+    pub bet_amount: u64,
+    pub fee_amount: u64,
+    pub bet_choice: u8,
+    pub outcome: BetOutcome,
+    pub payout_amount: u64,
+    pub vrf_result: [u8; 32],
+    pub bet_at: i64,
+    pub resolved_at: i64,
+    pub bump: u8,
+}
+
+impl PlayerBetV0 {
+    // no version, mint, bet_slot, from_balance, rent_payer or multiplier_bps
+    pub const LEN: usize = PlayerBet::LEN - 1 - 32 - 8 - 1 - 32 - 4;
+
+    /// Legacy bets were paid for by the player from their wallet. Their
+    /// multiplier was read from the config at resolution, so the caller passes
+    /// the one in force now; the resolution timeout counts from `slot`.
+    pub fn upgrade(self, mint: Pubkey, multiplier_bps: u32, slot: u64) -> PlayerBet {
+        PlayerBet {
+            version: PlayerBet::VERSION,
+            player: self.player,
+            game: self.game,
+            round_number: self.round_number,
+            mint,
+            bet_amount: self.bet_amount,
+            fee_amount: self.fee_amount,
+            bet_choice: self.bet_choice,
+            outcome: self.outcome,
+            payout_amount: self.payout_amount,
+            vrf_result: self.vrf_result,
+            bet_at: self.bet_at,
+            bet_slot: slot,
+            resolved_at: self.resolved_at,
+            from_balance: false,
+            rent_payer: self.player,
+            multiplier_bps,
+            bump: self.bump,
+        }
+    }
+}
+
+/// GachaPullResult before versioning (pulls cost the game's minimum bet each)
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GachaPullResultV0 {
+    pub player: Pubkey,
+    pub game: Pubkey,
+    pub pull_count: u8,
+    pub tiers: [u8; 10],
+    pub total_payout: u64,
+    pub vrf_result: [u8; 32],
+    pub resolved: bool,
+    pub pulled_at: i64,
+    pub bump: u8,
+}
+
+impl GachaPullResultV0 {
+    // no version, mint, total_cost, fee_amount, pulled_slot, resolved_at,
+    // from_balance, rent_payer or cost_per_pull
+    pub const LEN: usize = GachaPullResult::LEN - 1 - 32 - 8 - 8 - 8 - 8 - 1 - 32 - 8;
+
+    /// Legacy pulls were paid for by the player from their wallet at the
+    /// `cost_per_pull` in force now, without a platform fee on record; the
+    /// resolution timeout counts from `slot`.
+    /// Resolution time wasn't recorded, so settled pulls use their pull time.
+    pub fn upgrade(self, mint: Pubkey, cost_per_pull: u64, slot: u64) -> GachaPullResult {
+        GachaPullResult {
+            version: GachaPullResult::VERSION,
+            player: self.player,
+            game: self.game,
+            mint,
+            pull_count: self.pull_count,
+            tiers: self.tiers,
+            total_cost: cost_per_pull * self.pull_count as u64,
+            fee_amount: 0,
+            total_payout: self.total_payout,
+            vrf_result: self.vrf_result,
+            resolved: self.resolved,
+            pulled_at: self.pulled_at,
+            pulled_slot: slot,
+            resolved_at: if self.resolved { self.pulled_at } else { 0 },
+            from_balance: false,
+            rent_payer: self.player,
+            cost_per_pull,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Decode an account of type `T` stored in its current layout or in its
+/// legacy (version 0) layout of `legacy_len` bytes. Returns the account
+/// upgraded to the current layout and the version it was stored in, which
+/// for versioned layouts is the byte right after the discriminator.
+pub fn load_versioned<T, L>(
+    data: &[u8],
+    legacy_len: usize,
+    upgrade: impl FnOnce(L) -> T,
+) -> Result<(T, u8)>
+where
+    T: AccountDeserialize + Discriminator,
+    L: AnchorDeserialize,
+{
+    require!(
+        data.len() > 8 && data[..8] == T::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    if data.len() == legacy_len {
+        let legacy = L::deserialize(&mut &data[8..])
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        return Ok((upgrade(legacy), 0));
+    }
+
+    Ok((T::try_deserialize(&mut &data[..])?, data[8]))
+}
+
 // ============ HELPER FUNCTIONS ============
 
 impl PrizeTier {
@@ -383,6 +1993,8 @@ pub fn calculate_crash_point(vrf_result: &[u8; 32]) -> u32 {
 }
 
 /// Calculate coin flip result from VRF
+// `u8::is_multiple_of` is newer than the Solana toolchain's rustc
+#[allow(clippy::manual_is_multiple_of)]
 pub fn calculate_coinflip_result(vrf_result: &[u8; 32]) -> CoinChoice {
     if vrf_result[0] % 2 == 0 {
         CoinChoice::Heads
@@ -392,7 +2004,167 @@ pub fn calculate_coinflip_result(vrf_result: &[u8; 32]) -> CoinChoice {
 }
 
 /// Calculate jackpot winner index from VRF
-pub fn calculate_jackpot_winner(vrf_result: &[u8; 32], total_tickets: u32) -> u32 {
-    let random = u32::from_le_bytes([vrf_result[0], vrf_result[1], vrf_result[2], vrf_result[3]]);
+pub fn calculate_jackpot_winner(vrf_result: &[u8; 32], total_tickets: u64) -> u64 {
+    let random = u64::from_le_bytes(vrf_result[..8].try_into().unwrap());
     random % total_tickets
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_with_profit(realized_profit: i64, burned_profit_mark: i64, burn_bps: u16) -> GameState {
+        let mut game = GameStateV0 {
+            authority: Pubkey::default(),
+            game_type: GameType::CoinFlip,
+            slug: [0u8; 32],
+            config: GameConfig::default(),
+            cc_mint: Pubkey::default(),
+            escrow_bump: 0,
+            is_active: true,
+            total_volume: 0,
+            total_fees: 0,
+            current_round: 0,
+            created_at: 0,
+            _reserved: [0u8; 64],
+        }
+        .upgrade();
+        game.realized_profit = realized_profit;
+        game.burned_profit_mark = burned_profit_mark;
+        game.burn_bps = burn_bps;
+        game
+    }
+
+    #[test]
+    fn first_deposit_into_empty_bankroll_mints_one_to_one() {
+        assert_eq!(Bankroll::shares_for_deposit(1_000, 0, 0), Some(1_000));
+    }
+
+    #[test]
+    fn unowned_liquidity_must_be_seeded_before_deposits() {
+        assert_eq!(Bankroll::shares_for_deposit(1_000, 5_000, 0), None);
+
+        // Seeding mints shares 1:1 for the existing liquidity
+        assert_eq!(Bankroll::shares_for_deposit(1_000, 5_000, 5_000), Some(1_000));
+    }
+
+    #[test]
+    fn deposit_into_drained_bankroll_is_refused() {
+        assert_eq!(Bankroll::shares_for_deposit(1_000, 0, 5_000), None);
+    }
+
+    #[test]
+    fn deposit_shares_round_toward_the_pool() {
+        // 1_000 * 1_000 / 3_000 = 333.33
+        let shares = Bankroll::shares_for_deposit(1_000, 3_000, 1_000).unwrap();
+        assert_eq!(shares, 333);
+
+        // The new shares redeem for no more than was deposited
+        let redeemed = Bankroll::amount_for_shares(shares, 4_000, 1_000 + shares);
+        assert!(redeemed <= 1_000);
+    }
+
+    #[test]
+    fn redemptions_are_pro_rata_and_round_toward_the_pool() {
+        assert_eq!(Bankroll::amount_for_shares(250, 4_000, 1_000), 1_000);
+        // 1 * 10 / 3 = 3.33
+        assert_eq!(Bankroll::amount_for_shares(1, 10, 3), 3);
+    }
+
+    #[test]
+    fn redemptions_without_shares_or_liquidity_pay_nothing() {
+        assert_eq!(Bankroll::amount_for_shares(1_000, 5_000, 0), 0);
+        assert_eq!(Bankroll::amount_for_shares(1_000, 0, 1_000), 0);
+    }
+
+    #[test]
+    fn pending_burn_takes_its_share_of_new_profit() {
+        assert_eq!(game_with_profit(10_000, 2_000, 2_500).pending_burn(), 2_000);
+        // 3 * 5_000 / 10_000 = 1.5
+        assert_eq!(game_with_profit(3, 0, 5_000).pending_burn(), 1);
+    }
+
+    #[test]
+    fn pending_burn_is_zero_without_new_profit() {
+        assert_eq!(game_with_profit(2_000, 2_000, 2_500).pending_burn(), 0);
+        assert_eq!(game_with_profit(1_000, 2_000, 2_500).pending_burn(), 0);
+        assert_eq!(game_with_profit(-5_000, 0, 2_500).pending_burn(), 0);
+        assert_eq!(game_with_profit(10_000, 0, 0).pending_burn(), 0);
+    }
+
+    fn martingale(stop_loss: u64, take_profit: u64) -> AutoBetStrategy {
+        AutoBetStrategy {
+            base_bet: 100,
+            bet_count: 10,
+            on_win_bps: 0,
+            on_loss_bps: 20_000,
+            stop_loss,
+            take_profit,
+        }
+    }
+
+    fn session(strategy: AutoBetStrategy, current_bet: u64, wagered: u64, won: u64) -> AutoBet {
+        AutoBet {
+            version: AutoBet::VERSION,
+            player: Pubkey::default(),
+            game: Pubkey::default(),
+            bankroll: Pubkey::default(),
+            choice: CoinChoice::Heads,
+            strategy,
+            current_bet,
+            bets_placed: 1,
+            wagered,
+            won,
+            active: true,
+            created_at: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn martingale_doubles_on_loss_and_resets_on_win() {
+        let strategy = martingale(0, 0);
+        assert_eq!(strategy.next_bet(100, false), 200);
+        assert_eq!(strategy.next_bet(400, false), 800);
+        assert_eq!(strategy.next_bet(800, true), 100);
+    }
+
+    #[test]
+    fn next_bet_saturates_and_is_clamped_to_the_bet_limits() {
+        let strategy = martingale(0, 0);
+        assert_eq!(strategy.next_bet(u64::MAX, false), u64::MAX);
+        assert_eq!(strategy.next_bet(u64::MAX, false).clamp(100, 1_000), 1_000);
+
+        let shrinking = AutoBetStrategy { on_win_bps: 5_000, ..strategy };
+        assert_eq!(shrinking.next_bet(150, true).clamp(100, 1_000), 100);
+    }
+
+    #[test]
+    fn stop_loss_stops_before_a_bet_that_could_exceed_it() {
+        // Down 300 with a 200 bet next: a loss would land exactly on the limit
+        let at_limit = session(martingale(500, 0), 200, 300, 0);
+        assert_eq!(at_limit.stop_reason(), None);
+
+        let past_limit = session(martingale(500, 0), 201, 300, 0);
+        assert_eq!(past_limit.stop_reason(), Some(AutoBetStop::StopLoss));
+    }
+
+    #[test]
+    fn take_profit_stops_once_the_target_is_reached() {
+        let below = session(martingale(0, 500), 100, 1_000, 1_499);
+        assert_eq!(below.stop_reason(), None);
+
+        let reached = session(martingale(0, 500), 100, 1_000, 1_500);
+        assert_eq!(reached.stop_reason(), Some(AutoBetStop::TakeProfit));
+    }
+
+    #[test]
+    fn zero_limits_are_disabled_and_completion_wins() {
+        let unlimited = session(martingale(0, 0), 1_000_000, 1_000_000, 0);
+        assert_eq!(unlimited.stop_reason(), None);
+
+        let mut finished = session(martingale(500, 500), 100, 1_000, 2_000);
+        finished.bets_placed = finished.strategy.bet_count;
+        assert_eq!(finished.stop_reason(), Some(AutoBetStop::Completed));
+    }
+}
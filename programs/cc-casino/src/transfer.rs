@@ -0,0 +1,267 @@
+//! Token and lamport movement helpers for CC Casino
+//!
+//! Every instruction goes through the token interface so games work with both
+//! legacy SPL mints and Token-2022 mints. Mints with the transfer-fee extension
+//! withhold part of each transfer: stakes are credited net of the inbound fee,
+//! and game payouts are grossed up so the player receives the full amount.
+//!
+//! Native SOL games skip the token accounts entirely and keep their liquidity
+//! in a lamport vault PDA owned by the system program.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::state::{Bankroll, Currency, GameState};
+use crate::CasinoError;
+
+/// Fee withheld by the mint when `amount` is transferred (0 without the extension)
+pub fn transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let Ok(fee_config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+
+    fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or_else(|| error!(CasinoError::TransferFeeOverflow))
+}
+
+/// Amount to send so the recipient receives `net_amount` after transfer fees
+pub fn gross_up(mint: &InterfaceAccount<Mint>, net_amount: u64) -> Result<u64> {
+    if net_amount == 0 {
+        return Ok(0);
+    }
+
+    let mint_info = mint.to_account_info();
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let Ok(fee_config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(net_amount);
+    };
+
+    let fee = fee_config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+        .ok_or(CasinoError::TransferFeeOverflow)?;
+    net_amount
+        .checked_add(fee)
+        .ok_or_else(|| error!(CasinoError::TransferFeeOverflow))
+}
+
+/// Transfer from a signer-owned token account into a bankroll vault.
+/// Returns the amount the vault actually received.
+pub fn transfer_to_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    let fee = transfer_fee(mint, amount)?;
+
+    let cpi_accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: vault.to_account_info(),
+        authority: authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+
+    Ok(amount - fee)
+}
+
+/// Transfer `amount` out of a bankroll vault, signed by the bankroll PDA
+pub fn transfer_from_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    bankroll: &Account<'info, Bankroll>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"bankroll".as_ref(), bankroll.mint.as_ref(), &[bankroll.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: bankroll.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+/// Move lamports from a signer into a game's native SOL vault
+pub fn transfer_to_sol_vault<'info>(
+    system_program: &Program<'info, System>,
+    from: &AccountInfo<'info>,
+    sol_vault: &SystemAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = anchor_lang::system_program::Transfer {
+        from: from.clone(),
+        to: sol_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+    anchor_lang::system_program::transfer(cpi_ctx, amount)
+}
+
+/// Pay lamports out of a game's native SOL vault, signed by the vault PDA
+pub fn transfer_from_sol_vault<'info>(
+    system_program: &Program<'info, System>,
+    game: Pubkey,
+    sol_vault_bump: u8,
+    sol_vault: &SystemAccount<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"sol_vault".as_ref(), game.as_ref(), &[sol_vault_bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = anchor_lang::system_program::Transfer {
+        from: sol_vault.to_account_info(),
+        to: to.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    anchor_lang::system_program::transfer(cpi_ctx, amount)
+}
+
+/// Lamports held by a native SOL vault above its rent-exempt minimum
+pub fn sol_vault_balance(sol_vault: &SystemAccount) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    Ok(sol_vault.lamports().saturating_sub(rent_exempt))
+}
+
+/// The accounts a game instruction moves its currency through. Token games
+/// pass the mint, bankroll, vault and token program; native SOL games pass
+/// the lamport vault instead.
+pub struct GameFunds<'a, 'info> {
+    game: Pubkey,
+    currency: Currency,
+    sol_vault_bump: u8,
+    mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    bankroll: Option<&'a Account<'info, Bankroll>>,
+    vault: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    token_program: Option<&'a Interface<'info, TokenInterface>>,
+    sol_vault: Option<&'a SystemAccount<'info>>,
+    system_program: &'a Program<'info, System>,
+}
+
+impl<'a, 'info> GameFunds<'a, 'info> {
+    pub fn new(
+        game: &Account<'info, GameState>,
+        mint: Option<&'a InterfaceAccount<'info, Mint>>,
+        bankroll: Option<&'a Account<'info, Bankroll>>,
+        vault: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        token_program: Option<&'a Interface<'info, TokenInterface>>,
+        sol_vault: Option<&'a SystemAccount<'info>>,
+        system_program: &'a Program<'info, System>,
+    ) -> Self {
+        Self {
+            game: game.key(),
+            currency: game.currency,
+            sol_vault_bump: game.sol_vault_bump,
+            mint,
+            bankroll,
+            vault,
+            token_program,
+            sol_vault,
+            system_program,
+        }
+    }
+
+    /// Mint the instruction moves (default for native SOL games)
+    pub fn mint_key(&self) -> Pubkey {
+        self.mint.map(|mint| mint.key()).unwrap_or_default()
+    }
+
+    /// Liquidity currently held by the vault backing this game
+    pub fn balance(&self) -> Result<u64> {
+        match self.currency {
+            Currency::Token => Ok(required(self.bankroll)?.liquidity(required(self.vault)?.amount)),
+            Currency::Native => sol_vault_balance(required(self.sol_vault)?),
+        }
+    }
+
+    /// Amount the vault must send for the recipient to receive `net_amount`
+    pub fn gross_up(&self, net_amount: u64) -> Result<u64> {
+        match self.currency {
+            Currency::Token => gross_up(required(self.mint)?, net_amount),
+            Currency::Native => Ok(net_amount),
+        }
+    }
+
+    /// Collect a stake from the player. Returns the amount the vault received.
+    pub fn collect(
+        &self,
+        player: &AccountInfo<'info>,
+        player_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<u64> {
+        match self.currency {
+            Currency::Token => transfer_to_vault(
+                required(self.token_program)?,
+                required(self.mint)?,
+                required(player_token_account)?,
+                required(self.vault)?,
+                player,
+                amount,
+            ),
+            Currency::Native => {
+                transfer_to_sol_vault(self.system_program, player, required(self.sol_vault)?, amount)?;
+                Ok(amount)
+            }
+        }
+    }
+
+    /// Send `amount` from the vault to the player, out of liquidity only
+    /// (never out of other players' internal balances)
+    pub fn pay(
+        &self,
+        player: &AccountInfo<'info>,
+        player_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(self.balance()? >= amount, CasinoError::InsufficientEscrow);
+
+        match self.currency {
+            Currency::Token => transfer_from_vault(
+                required(self.token_program)?,
+                required(self.mint)?,
+                required(self.bankroll)?,
+                required(self.vault)?,
+                required(player_token_account)?,
+                amount,
+            ),
+            Currency::Native => transfer_from_sol_vault(
+                self.system_program,
+                self.game,
+                self.sol_vault_bump,
+                required(self.sol_vault)?,
+                player,
+                amount,
+            ),
+        }
+    }
+}
+
+/// Unwrap an optional account the game's currency depends on
+pub fn required<T>(account: Option<T>) -> Result<T> {
+    account.ok_or_else(|| error!(CasinoError::MissingCurrencyAccount))
+}